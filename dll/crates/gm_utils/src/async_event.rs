@@ -0,0 +1,108 @@
+//! Utilities to trigger Gamemaker async events from native code.
+//!
+//! Gamemaker calls `RegisterCallbacks` on every extension exporting it, handing over the
+//! functions needed to create a ds_map and queue it as the `async_load` of an async event.
+//! Pass those pointers to [`register_callbacks`], after which [`DsMap`] can be used from any
+//! thread.
+
+use std::{ffi::CString, os::raw::c_char, sync::OnceLock};
+
+type CreateAsyncEventWithDsMap = unsafe extern "C" fn(i32, i32);
+type DsMapCreate = unsafe extern "C" fn(i32, ...) -> i32;
+type DsMapAddDouble = unsafe extern "C" fn(i32, *const c_char, f64) -> bool;
+type DsMapAddString = unsafe extern "C" fn(i32, *const c_char, *const c_char) -> bool;
+
+struct Callbacks {
+    create_async_event: CreateAsyncEventWithDsMap,
+    ds_map_create: DsMapCreate,
+    ds_map_add_double: DsMapAddDouble,
+    ds_map_add_string: DsMapAddString,
+}
+
+static CALLBACKS: OnceLock<Callbacks> = OnceLock::new();
+
+/// The async event a [`DsMap`] is dispatched to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(i32)]
+pub enum AsyncEvent {
+    Http = 62,
+    Networking = 68,
+    Steam = 69,
+    Social = 70,
+    System = 75,
+}
+
+/// Stores the callbacks Gamemaker passes to `RegisterCallbacks`, in the order it passes them.
+///
+/// Only the first registration is kept, later ones are ignored.
+/// # Safety
+/// The pointers must be the ones Gamemaker passed to `RegisterCallbacks`.
+pub unsafe fn register_callbacks(
+    create_async_event: *mut c_char,
+    ds_map_create: *mut c_char,
+    ds_map_add_double: *mut c_char,
+    ds_map_add_string: *mut c_char,
+) {
+    if create_async_event.is_null()
+        || ds_map_create.is_null()
+        || ds_map_add_double.is_null()
+        || ds_map_add_string.is_null()
+    {
+        return;
+    }
+    let _ = CALLBACKS.set(Callbacks {
+        create_async_event: std::mem::transmute::<*mut c_char, CreateAsyncEventWithDsMap>(
+            create_async_event,
+        ),
+        ds_map_create: std::mem::transmute::<*mut c_char, DsMapCreate>(ds_map_create),
+        ds_map_add_double: std::mem::transmute::<*mut c_char, DsMapAddDouble>(ds_map_add_double),
+        ds_map_add_string: std::mem::transmute::<*mut c_char, DsMapAddString>(ds_map_add_string),
+    });
+}
+
+/// Whether Gamemaker registered its callbacks, i.e. whether [`DsMap::new`] can succeed.
+#[inline]
+pub fn is_registered() -> bool {
+    CALLBACKS.get().is_some()
+}
+
+/// Strings are cut at their first NUL byte, as that's where Gamemaker would stop reading them.
+fn to_c_string(s: &str) -> CString {
+    let s = s.split('\0').next().unwrap_or_default();
+    CString::new(s).unwrap_or_default()
+}
+
+/// A ds_map which becomes the `async_load` of an async event once dispatched.
+pub struct DsMap {
+    callbacks: &'static Callbacks,
+    index: i32,
+}
+
+impl DsMap {
+    /// Creates an empty ds_map, returns `None` if the callbacks were never registered.
+    pub fn new() -> Option<Self> {
+        let callbacks = CALLBACKS.get()?;
+        // SAFETY: The pointer was handed over by Gamemaker through `RegisterCallbacks`.
+        let index = unsafe { (callbacks.ds_map_create)(0) };
+        Some(Self { callbacks, index })
+    }
+
+    pub fn add_double(&mut self, key: &str, value: f64) {
+        let key = to_c_string(key);
+        // SAFETY: The pointer was handed over by Gamemaker through `RegisterCallbacks`.
+        unsafe { (self.callbacks.ds_map_add_double)(self.index, key.as_ptr(), value) };
+    }
+
+    pub fn add_string(&mut self, key: &str, value: &str) {
+        let key = to_c_string(key);
+        let value = to_c_string(value);
+        // SAFETY: The pointer was handed over by Gamemaker through `RegisterCallbacks`.
+        unsafe { (self.callbacks.ds_map_add_string)(self.index, key.as_ptr(), value.as_ptr()) };
+    }
+
+    /// Queues the map as the `async_load` of `event`, Gamemaker frees it once the event ran.
+    pub fn dispatch(self, event: AsyncEvent) {
+        // SAFETY: The pointer was handed over by Gamemaker through `RegisterCallbacks`.
+        unsafe { (self.callbacks.create_async_event)(self.index, event as i32) };
+    }
+}
@@ -1,5 +1,6 @@
 //! A crate containing utilities for interfacing with Gamemaker's Extension FFI
 
+pub mod async_event;
 pub mod buffer;
 pub mod func;
 
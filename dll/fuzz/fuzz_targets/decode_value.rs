@@ -0,0 +1,10 @@
+//! Feeds arbitrary input to the value decoder, see `crystal_dll::fuzz_decode`.
+//!
+//! Run from `dll/` with `cargo +nightly fuzz run decode_value`, which starts from the encodings
+//! in `corpus/decode_value`.
+
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| crystal_dll::fuzz_decode(data));
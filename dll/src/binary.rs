@@ -0,0 +1,347 @@
+//! Binary encoding of notifications, read in GML with `buffer_read`.
+//!
+//! All numbers are little-endian. A record is laid out as
+//!
+//! | type     | content                                   |
+//! |----------|-------------------------------------------|
+//! | `u32`    | byte length of the rest of the record     |
+//! | `u64`    | sequence number                           |
+//! | string   | kind                                      |
+//! | `u8`     | field count                               |
+//! | field... | name (string), type (`u8`) and payload    |
+//!
+//! Strings are their byte length as `u32` followed by the UTF-8 bytes and a NUL byte which
+//! isn't counted in the length, so they can be read with either the length or `buffer_string`.
+//! Bytes are their length as `u32` followed by the bytes.
+//!
+//! Field types are `0` for a `f64`, `1` for a string, `2` for bytes and `3` for a value, which
+//! is a tag (`u8`) followed by its payload:
+//!
+//! | tag   | payload                                        |
+//! |-------|------------------------------------------------|
+//! | `0`   | `i64`                                          |
+//! | `1`   | `f64`                                          |
+//! | `2`   | `u8`, `0` or `1`                               |
+//! | `3`   | string                                         |
+//! | `4`   | bytes                                          |
+//! | `5`   | element count (`u32`), then the values         |
+//! | `6`   | member count (`u32`), then name (string) and value of each |
+//! | `254` | none, `null`                                   |
+//! | `255` | none, the value was removed or is missing      |
+
+use crystal_server::types::{OptionalValue, Value};
+
+use crate::{
+    notifications::{Field, Notification},
+    value::members,
+};
+
+fn write_str(out: &mut Vec<u8>, s: &str) {
+    out.extend_from_slice(&(s.len() as u32).to_le_bytes());
+    out.extend_from_slice(s.as_bytes());
+    out.push(0);
+}
+
+fn write_bytes(out: &mut Vec<u8>, bytes: &[u8]) {
+    out.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+    out.extend_from_slice(bytes);
+}
+
+pub(crate) fn write_value(out: &mut Vec<u8>, value: &Value) {
+    match value {
+        Value::Int(val) => {
+            out.push(0);
+            out.extend_from_slice(&val.to_le_bytes());
+        }
+        Value::Float(val) => {
+            out.push(1);
+            out.extend_from_slice(&val.to_le_bytes());
+        }
+        Value::Bool(val) => out.extend_from_slice(&[2, *val as u8]),
+        Value::String(val) => {
+            out.push(3);
+            write_str(out, val);
+        }
+        Value::Buffer(val) => {
+            out.push(4);
+            write_bytes(out, val);
+        }
+        Value::Array(val) => {
+            out.push(5);
+            out.extend_from_slice(&(val.len() as u32).to_le_bytes());
+            for val in val {
+                write_value(out, val);
+            }
+        }
+        Value::Struct(val) => {
+            out.push(6);
+            out.extend_from_slice(&(val.len() as u32).to_le_bytes());
+            for (name, val) in members(val) {
+                write_str(out, name);
+                write_value(out, val);
+            }
+        }
+        Value::Null => out.push(254),
+    }
+}
+
+fn write_optional_value(out: &mut Vec<u8>, value: &OptionalValue) {
+    match value {
+        OptionalValue::Some(value) => write_value(out, value),
+        OptionalValue::None => out.push(255),
+    }
+}
+
+/// Appends the record of `notification` to `out`.
+pub(crate) fn write_record(out: &mut Vec<u8>, seq: u64, notification: &Notification) {
+    let start = out.len();
+    out.extend_from_slice(&[0; 4]);
+    out.extend_from_slice(&seq.to_le_bytes());
+    write_str(out, notification.kind());
+    let fields = notification.fields();
+    out.push(fields.len() as u8);
+    for (name, field) in &fields {
+        write_str(out, name);
+        match field {
+            Field::Real(val) => {
+                out.push(0);
+                out.extend_from_slice(&val.to_le_bytes());
+            }
+            Field::Text(val) => {
+                out.push(1);
+                write_str(out, val);
+            }
+            Field::Bytes(val) => {
+                out.push(2);
+                write_bytes(out, val);
+            }
+            Field::Value(val) => {
+                out.push(3);
+                write_optional_value(out, val);
+            }
+        }
+    }
+    let len = (out.len() - start - 4) as u32;
+    out[start..start + 4].copy_from_slice(&len.to_le_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, time::Duration};
+
+    use crystal_server::types::{AdminAction, DataUpdate, OptionalValue, Value};
+    use gm_utils::parsing::complete::{bool, le_f64, le_i64, le_u8, le_u32, le_u64, string};
+    use nom::{
+        IResult,
+        bytes::complete::take,
+        combinator::{all_consuming, map_res, verify},
+        multi::count,
+    };
+
+    use super::*;
+    use crate::connection::{Category, ConnectError};
+
+    fn parse_str(input: &[u8]) -> IResult<&[u8], String> {
+        let (input, len) = le_u32(input)?;
+        let (input, s) =
+            verify(string, |s: &std::ffi::CStr| s.count_bytes() == len as usize)(input)?;
+        Ok((input, s.to_str().unwrap().to_owned()))
+    }
+
+    fn parse_bytes(input: &[u8]) -> IResult<&[u8], Vec<u8>> {
+        let (input, len) = le_u32(input)?;
+        let (input, bytes) = take(len)(input)?;
+        Ok((input, bytes.to_vec()))
+    }
+
+    fn parse_value(input: &[u8]) -> IResult<&[u8], OptionalValue> {
+        let (input, tag) = le_u8(input)?;
+        let (input, value) = match tag {
+            0 => {
+                let (input, val) = le_i64(input)?;
+                (input, Value::Int(val))
+            }
+            1 => {
+                let (input, val) = le_f64(input)?;
+                (input, Value::Float(val))
+            }
+            2 => {
+                let (input, val) = bool(input)?;
+                (input, Value::Bool(val))
+            }
+            3 => {
+                let (input, val) = parse_str(input)?;
+                (input, Value::String(val))
+            }
+            4 => {
+                let (input, val) = parse_bytes(input)?;
+                (input, Value::Buffer(val))
+            }
+            5 => {
+                let (input, len) = le_u32(input)?;
+                let (input, val) = count(
+                    map_res(parse_value, |val| match val {
+                        OptionalValue::Some(val) => Ok(val),
+                        OptionalValue::None => Err(()),
+                    }),
+                    len as usize,
+                )(input)?;
+                (input, Value::Array(val))
+            }
+            6 => {
+                let (mut input, len) = le_u32(input)?;
+                let mut val = HashMap::new();
+                for _ in 0..len {
+                    let (rest, name) = parse_str(input)?;
+                    let (rest, member) = parse_value(rest)?;
+                    let OptionalValue::Some(member) = member else {
+                        panic!("struct member without a value");
+                    };
+                    val.insert(name, member);
+                    input = rest;
+                }
+                (input, Value::Struct(val))
+            }
+            254 => (input, Value::Null),
+            255 => return Ok((input, OptionalValue::None)),
+            tag => panic!("unknown value tag {tag}"),
+        };
+        Ok((input, OptionalValue::Some(value)))
+    }
+
+    fn parse_field(input: &[u8]) -> IResult<&[u8], (String, Field)> {
+        let (input, name) = parse_str(input)?;
+        let (input, kind) = le_u8(input)?;
+        let (input, field) = match kind {
+            0 => {
+                let (input, val) = le_f64(input)?;
+                (input, Field::Real(val))
+            }
+            1 => {
+                let (input, val) = parse_str(input)?;
+                (input, Field::Text(val))
+            }
+            2 => {
+                let (input, val) = parse_bytes(input)?;
+                (input, Field::Bytes(val))
+            }
+            3 => {
+                let (input, val) = parse_value(input)?;
+                (input, Field::Value(val))
+            }
+            kind => panic!("unknown field type {kind}"),
+        };
+        Ok((input, (name, field)))
+    }
+
+    type Record = (u64, String, Vec<(String, Field)>);
+
+    fn parse_record(input: &[u8]) -> IResult<&[u8], Record> {
+        let (input, len) = le_u32(input)?;
+        let (rest, record) = take(len)(input)?;
+        let (_, record) = all_consuming(|record| {
+            let (record, seq) = le_u64(record)?;
+            let (record, kind) = parse_str(record)?;
+            let (record, len) = le_u8(record)?;
+            let (record, fields) = count(parse_field, len as usize)(record)?;
+            Ok((record, (seq, kind, fields)))
+        })(record)?;
+        Ok((rest, record))
+    }
+
+    fn sample_value() -> Value {
+        Value::Struct(HashMap::from([
+            (String::from("x"), Value::Float(-12.5)),
+            (String::from("hp"), Value::Int(i64::MIN)),
+            (
+                String::from("name"),
+                Value::String(String::from("Ünïcode ✓")),
+            ),
+            (String::from("alive"), Value::Bool(true)),
+            (String::from("blob"), Value::Buffer(vec![0, 1, 0, 255])),
+            (
+                String::from("items"),
+                Value::Array(vec![Value::Null, Value::Array(Vec::new())]),
+            ),
+        ]))
+    }
+
+    fn samples() -> Vec<Notification> {
+        vec![
+            Notification::Data(DataUpdate::UpdateVariable(
+                7,
+                String::from("pos"),
+                OptionalValue::Some(sample_value()),
+            )),
+            Notification::Data(DataUpdate::UpdateSyncVariable(
+                u64::MAX,
+                3,
+                String::from(""),
+                OptionalValue::None,
+            )),
+            Notification::Data(DataUpdate::AdminAction(AdminAction::Ban(
+                String::from("reason;with:separators"),
+                1_700_000_000,
+            ))),
+            Notification::Data(DataUpdate::FetchBdb(
+                String::from("bdb"),
+                Some(vec![0, 0, 0]),
+            )),
+            Notification::Data(DataUpdate::FetchBdb(String::from("bdb"), None)),
+            Notification::Data(DataUpdate::P2P(None, -4, vec![sample_value()])),
+            Notification::Data(DataUpdate::UpdatePlayerIni(
+                None,
+                String::from("section"),
+                String::from("key"),
+                OptionalValue::Some(Value::Null),
+            )),
+            Notification::Data(DataUpdate::Disconnected()),
+            Notification::Ticket {
+                id: 42,
+                result: Err(String::from("the call panicked")),
+            },
+            Notification::ConnectResult(Ok(())),
+            Notification::ConnectResult(Err(ConnectError {
+                category: Category::Refused,
+                message: String::from("connection refused"),
+            })),
+            Notification::Data(DataUpdate::Reconnecting()),
+            Notification::Reconnecting {
+                attempt: 3,
+                delay: Duration::from_millis(1500),
+            },
+            Notification::ReconnectFailed { attempts: 5 },
+        ]
+    }
+
+    #[test]
+    fn records_round_trip() {
+        let mut out = Vec::new();
+        for (seq, notification) in samples().iter().enumerate() {
+            write_record(&mut out, seq as u64, notification);
+        }
+        let mut input = &out[..];
+        for (seq, notification) in samples().iter().enumerate() {
+            let (rest, (parsed_seq, kind, fields)) = parse_record(input).unwrap();
+            assert_eq!(parsed_seq, seq as u64);
+            assert_eq!(kind, notification.kind());
+            let expected = notification
+                .fields()
+                .into_iter()
+                .map(|(name, field)| (name.to_owned(), field))
+                .collect::<Vec<_>>();
+            assert_eq!(fields, expected);
+            input = rest;
+        }
+        assert!(input.is_empty());
+    }
+
+    #[test]
+    fn truncated_records_are_rejected() {
+        let mut out = Vec::new();
+        write_record(&mut out, 1, &samples()[0]);
+        for len in 0..out.len() {
+            assert!(parse_record(&out[..len]).is_err());
+        }
+    }
+}
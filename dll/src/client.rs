@@ -0,0 +1,190 @@
+//! The task owning the [`CrystalServer`].
+//!
+//! Exports never touch the client directly, they send a [`Command`] to the task instead and,
+//! if they need an answer, wait for it on a oneshot channel. Everything that can be answered
+//! without asking the server (connection state, ping, player id and name) is published as a
+//! [`State`] snapshot after every command, so reading it never waits behind a pending call.
+
+use std::{fmt::Display, future::Future, panic::AssertUnwindSafe, time::Duration};
+
+use crystal_server::client::CrystalServer;
+use futures_util::{FutureExt, future::BoxFuture};
+use tokio::{
+    sync::{mpsc, oneshot, watch},
+    time::MissedTickBehavior,
+};
+
+use crate::{RUNTIME, config};
+
+/// How often the [`State`] snapshot is refreshed while no commands are coming in.
+const STATE_REFRESH: Duration = Duration::from_millis(50);
+
+/// A call to run against the client, in the order it was sent.
+pub(crate) type Job = Box<dyn for<'a> FnOnce(&'a mut CrystalServer) -> BoxFuture<'a, ()> + Send>;
+
+pub(crate) enum Command {
+    /// Runs a call against the client.
+    Run(Job),
+}
+
+/// Read-only view of the client, updated by the client task.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct State {
+    pub is_connected: bool,
+    pub is_connecting: bool,
+    pub is_loggedin: bool,
+    pub ping: f64,
+    pub player_id: Option<u64>,
+    pub player_name: Option<String>,
+}
+
+impl State {
+    async fn read(crystal: &CrystalServer) -> Self {
+        Self {
+            is_connected: crystal.is_connected().await,
+            is_connecting: crystal.is_connecting().await,
+            is_loggedin: crystal.is_loggedin().await,
+            ping: crystal.get_ping().await,
+            player_id: crystal.get_player_id().await,
+            player_name: crystal.get_player_name().await,
+        }
+    }
+}
+
+/// Returned when the client task dropped a call without answering, which only happens when
+/// the call panicked.
+#[derive(Debug)]
+pub(crate) struct CallPanicked;
+
+impl Display for CallPanicked {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("the call panicked")
+    }
+}
+
+/// Handle to the client task.
+pub(crate) struct Client {
+    commands: mpsc::UnboundedSender<Command>,
+    state: watch::Receiver<State>,
+}
+
+impl Client {
+    /// Moves `crystal` into a new client task.
+    pub fn spawn(crystal: CrystalServer) -> Self {
+        let (commands, rx) = mpsc::unbounded_channel();
+        let (state_tx, state) = watch::channel(State::default());
+        RUNTIME.get().spawn(Self::serve(crystal, rx, state_tx));
+        Self { commands, state }
+    }
+
+    async fn serve(
+        mut crystal: CrystalServer,
+        mut commands: mpsc::UnboundedReceiver<Command>,
+        state: watch::Sender<State>,
+    ) {
+        let mut refresh = tokio::time::interval(STATE_REFRESH);
+        refresh.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            tokio::select! {
+                command = commands.recv() => match command {
+                    Some(Command::Run(job)) => {
+                        // A panicking call only loses its own answer, the task keeps going.
+                        let _ = AssertUnwindSafe(job(&mut crystal)).catch_unwind().await;
+                    }
+                    None => break,
+                },
+                _ = refresh.tick() => (),
+            }
+            state.send_replace(State::read(&crystal).await);
+        }
+    }
+
+    /// The latest published snapshot of the client.
+    pub fn state(&self) -> State {
+        self.state.borrow().clone()
+    }
+
+    /// A receiver of every snapshot published from now on.
+    pub fn watch(&self) -> watch::Receiver<State> {
+        self.state.clone()
+    }
+
+    /// Queues `command` without waiting for it.
+    pub fn send(&self, command: Command) {
+        // The task only stops once every handle is gone.
+        let _ = self.commands.send(command);
+    }
+
+    /// Queues `f` right away and returns a future resolving to its result.
+    ///
+    /// Calls are run in the order they're queued, no matter when the future is polled.
+    pub fn run<R: Send + 'static>(
+        &self,
+        f: impl for<'a> FnOnce(&'a mut CrystalServer) -> BoxFuture<'a, R> + Send + 'static,
+    ) -> impl Future<Output = Result<R, CallPanicked>> + Send + 'static {
+        let (tx, rx) = oneshot::channel();
+        self.send(Command::Run(Box::new(move |crystal| {
+            Box::pin(async move {
+                let _ = tx.send(f(crystal).await);
+            })
+        })));
+        async move { rx.await.map_err(|_| CallPanicked) }
+    }
+
+    /// Runs `f` and blocks the calling thread until it's done, or `None` once the `call_timeout`
+    /// elapsed or if `f` panicked.
+    ///
+    /// A call which timed out still runs, only its result is dropped.
+    pub fn call<R: Send + 'static>(
+        &self,
+        f: impl for<'a> FnOnce(&'a mut CrystalServer) -> BoxFuture<'a, R> + Send + 'static,
+    ) -> Option<R> {
+        RUNTIME
+            .get()
+            .block_on(config::with_timeout(self.run(f)))
+            .ok()?
+            .inspect_err(|err| tracing::error!("client call failed: {err}"))
+            .ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn state_is_readable_during_calls() {
+        let client = Client::spawn(CrystalServer::init(""));
+        let (started_tx, started) = std::sync::mpsc::channel();
+        let (release, released) = oneshot::channel::<()>();
+        let call = client.run(move |_| {
+            Box::pin(async move {
+                started_tx.send(()).unwrap();
+                let _ = released.await;
+            })
+        });
+        started.recv().unwrap();
+        assert!(!client.state().is_connected);
+        release.send(()).unwrap();
+        RUNTIME.get().block_on(call).unwrap();
+    }
+
+    #[test]
+    fn panicking_calls_keep_the_task() {
+        let client = Client::spawn(CrystalServer::init(""));
+        assert!(
+            RUNTIME
+                .get()
+                .block_on(client.run(|_| Box::pin(async { panic!("run") })))
+                .is_err()
+        );
+        assert_eq!(
+            client.call(|_| Box::pin(async { panic!("call") })),
+            None::<()>
+        );
+        assert_eq!(
+            client.call(|crystal| Box::pin(async move { crystal.is_connected().await })),
+            Some(false)
+        );
+    }
+}
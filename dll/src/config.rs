@@ -0,0 +1,325 @@
+//! Settings picked before `__crystal_init` with `__crystal_configure`.
+//!
+//! | key                   | value                                               | when          |
+//! |-----------------------|-----------------------------------------------------|---------------|
+//! | `runtime`             | `multi_thread` (default), `current_thread` or `manual` | before start |
+//! | `worker_threads`      | threads of the `multi_thread` runtime, at least 1   | before start  |
+//! | `log_level`           | `off`, `error`, `warn`, `info` (default), `debug` or `trace` | before the first init |
+//! | `notification_limit`  | cap of the notification queue, `0` for none         | any time      |
+//! | `notification_policy` | `drop_oldest`, `drop_newest` or `coalesce`          | any time      |
+//! | `call_timeout`        | milliseconds a call may take, `0` (default) for no limit | any time |
+//! | `shutdown_timeout`    | milliseconds `__crystal_shutdown` waits, `1000` by default | any time |
+//!
+//! The runtime starts with the first export that needs it, which is usually `__crystal_init`.
+//! A `current_thread` runtime only runs while an export waits on it, so the client only makes
+//! progress during calls such as `__crystal_update`. Once `__crystal_shutdown` stopped the
+//! runtime, its settings can change again until the next start.
+//!
+//! `manual` needs the `manual-time` feature. It's a `current_thread` runtime whose clock stands
+//! still, except when `__crystal_advance_time` moves it forward, so timeouts, reconnect delays
+//! and other timers can be stepped through in tests. Unlike a paused tokio clock in tests, it
+//! doesn't jump to the next timer while a call waits, e.g. on the server, so only the `_async`
+//! exports time out, once the clock was moved past their `call_timeout`. Achievements reached
+//! meanwhile are stamped by this clock too, which starts at the wall-clock time the runtime did.
+//!
+//! A call which timed out keeps running in the background: a blocking export returns its
+//! default value, and the ticket of an `_async` export fails with "the call timed out".
+
+use std::{
+    future::Future,
+    sync::{
+        Once,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
+
+use chrono::{DateTime, Utc};
+use gm_utils::gm_func;
+use parking_lot::Mutex;
+use tokio::{
+    runtime::{Builder, Runtime},
+    time::Instant,
+};
+use tracing_subscriber::filter::LevelFilter;
+
+use crate::notifications;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Flavor {
+    MultiThread,
+    CurrentThread,
+    /// `CurrentThread` with a paused clock.
+    Manual,
+}
+
+struct Config {
+    flavor: Flavor,
+    worker_threads: Option<usize>,
+    log_level: LevelFilter,
+    call_timeout: Option<Duration>,
+    shutdown_timeout: Duration,
+}
+
+static CONFIG: Mutex<Config> = Mutex::new(Config {
+    flavor: Flavor::MultiThread,
+    worker_threads: None,
+    log_level: LevelFilter::INFO,
+    call_timeout: None,
+    shutdown_timeout: Duration::from_secs(1),
+});
+static STARTED: AtomicBool = AtomicBool::new(false);
+static INITIALIZED: AtomicBool = AtomicBool::new(false);
+static MANUAL_CLOCK: Mutex<Option<ManualClock>> = Mutex::new(None);
+
+/// Keeps the clock of the `manual` runtime where `__crystal_advance_time` moved it.
+struct ManualClock {
+    /// The wall-clock time the runtime started at.
+    epoch: DateTime<Utc>,
+    start: Instant,
+    /// Ends the blocking task which holds the clock once dropped.
+    _release: std::sync::mpsc::Sender<()>,
+}
+
+impl ManualClock {
+    /// Stops the paused clock of `runtime` from jumping to the next timer by itself.
+    ///
+    /// Tokio doesn't do that while a blocking task runs, so one runs until this is dropped.
+    fn hold(runtime: &Runtime) -> Self {
+        let (release, held) = std::sync::mpsc::channel::<()>();
+        runtime.spawn_blocking(move || {
+            let _ = held.recv();
+        });
+        Self {
+            epoch: Utc::now(),
+            start: runtime.block_on(async { Instant::now() }),
+            _release: release,
+        }
+    }
+}
+
+/// Builds the runtime as configured, after which its settings can't change anymore.
+pub(crate) fn runtime() -> Runtime {
+    STARTED.store(true, Ordering::Relaxed);
+    let config = CONFIG.lock();
+    let mut builder = match config.flavor {
+        Flavor::MultiThread => Builder::new_multi_thread(),
+        Flavor::CurrentThread | Flavor::Manual => Builder::new_current_thread(),
+    };
+    #[cfg(feature = "manual-time")]
+    builder.start_paused(config.flavor == Flavor::Manual);
+    if let Some(worker_threads) = config.worker_threads {
+        builder.worker_threads(worker_threads);
+    }
+    let runtime = builder.enable_all().build().unwrap();
+    if config.flavor == Flavor::Manual {
+        *MANUAL_CLOCK.lock() = Some(ManualClock::hold(&runtime));
+    }
+    runtime
+}
+
+/// Lets the clock of the `manual` runtime move by itself again, so `__crystal_shutdown` can
+/// wait out its `shutdown_timeout`.
+pub(crate) fn stopping() {
+    *MANUAL_CLOCK.lock() = None;
+}
+
+/// The unix time by the clock of the `manual` runtime, `None` with any other runtime.
+///
+/// Must be called from within the runtime.
+pub(crate) fn manual_time() -> Option<i64> {
+    let clock = MANUAL_CLOCK.lock();
+    let clock = clock.as_ref()?;
+    let elapsed = chrono::Duration::from_std(clock.start.elapsed()).ok()?;
+    Some((clock.epoch + elapsed).timestamp())
+}
+
+/// Lets the runtime settings change again, after `__crystal_shutdown` stopped the runtime.
+pub(crate) fn stopped() {
+    STARTED.store(false, Ordering::Relaxed);
+}
+
+/// Installs the logger with the configured log level, after which it can't change anymore.
+///
+/// Only the first call does anything, the logger is global and can't be installed twice.
+pub(crate) fn init_logging() {
+    static LOGGING: Once = Once::new();
+    LOGGING.call_once(|| {
+        INITIALIZED.store(true, Ordering::Relaxed);
+        let log_level = CONFIG.lock().log_level;
+        // Fails if the game embeds another library which installed one already.
+        let _ = tracing_subscriber::fmt()
+            .with_max_level(log_level)
+            .try_init();
+    });
+}
+
+/// How long `__crystal_shutdown` waits for pending calls and the runtime to stop.
+pub(crate) fn shutdown_timeout() -> Duration {
+    CONFIG.lock().shutdown_timeout
+}
+
+/// Returned by [`with_timeout`] when a call took longer than `call_timeout`.
+#[derive(Debug)]
+pub(crate) struct TimedOut;
+
+/// Runs `fut` within the configured `call_timeout`.
+pub(crate) async fn with_timeout<F: Future>(fut: F) -> Result<F::Output, TimedOut> {
+    let call_timeout = CONFIG.lock().call_timeout;
+    match call_timeout {
+        Some(call_timeout) => tokio::time::timeout(call_timeout, fut)
+            .await
+            .map_err(|_| TimedOut),
+        None => Ok(fut.await),
+    }
+}
+
+fn configure(key: &str, value: &str) -> Result<(), String> {
+    let invalid = || format!("invalid value {value:?} for {key}");
+    let millis = || {
+        let ms = value.parse::<f64>().map_err(|_| invalid())?;
+        if !(ms >= 0.0 && ms.is_finite()) {
+            return Err(invalid());
+        }
+        Ok(Duration::from_secs_f64(ms / 1000.0))
+    };
+    let before_start = || {
+        if STARTED.load(Ordering::Relaxed) {
+            Err(format!("{key} can't change once the runtime started"))
+        } else {
+            Ok(())
+        }
+    };
+    let mut config = CONFIG.lock();
+    match key {
+        "runtime" => {
+            let flavor = match value {
+                "multi_thread" => Flavor::MultiThread,
+                "current_thread" => Flavor::CurrentThread,
+                "manual" if cfg!(feature = "manual-time") => Flavor::Manual,
+                "manual" => return Err(format!("{key} {value} needs the manual-time feature")),
+                _ => return Err(invalid()),
+            };
+            before_start()?;
+            config.flavor = flavor;
+        }
+        "worker_threads" => {
+            let worker_threads = value.parse::<usize>().map_err(|_| invalid())?;
+            if worker_threads == 0 {
+                return Err(invalid());
+            }
+            before_start()?;
+            config.worker_threads = Some(worker_threads);
+        }
+        "log_level" => {
+            if INITIALIZED.load(Ordering::Relaxed) {
+                return Err(format!("{key} can't change after __crystal_init"));
+            }
+            config.log_level = value.parse().map_err(|_| invalid())?;
+        }
+        "notification_limit" => {
+            notifications::set_limit(value.parse().map_err(|_| invalid())?);
+        }
+        "notification_policy" => {
+            if !notifications::set_policy(value) {
+                return Err(invalid());
+            }
+        }
+        "call_timeout" => {
+            let call_timeout = millis()?;
+            config.call_timeout = (!call_timeout.is_zero()).then_some(call_timeout);
+        }
+        "shutdown_timeout" => config.shutdown_timeout = millis()?,
+        _ => return Err(format!("unknown key {key:?}")),
+    }
+    Ok(())
+}
+
+/// Moves the clock of the `manual` runtime forward by `ms` milliseconds, running every timer
+/// that expires on the way. Returns false, doing nothing, with any other runtime.
+#[gm_func]
+pub fn __crystal_advance_time(ms: f64) -> bool {
+    #[cfg(feature = "debug")]
+    println!("advance_time({ms:?})");
+    if CONFIG.lock().flavor != Flavor::Manual || !(ms >= 0.0 && ms.is_finite()) {
+        return false;
+    }
+    #[cfg(feature = "manual-time")]
+    crate::RUNTIME
+        .get()
+        .block_on(tokio::time::advance(Duration::from_secs_f64(ms / 1000.0)));
+    true
+}
+
+/// Sets `key` to `value` (see the table above), returns why it couldn't be set or "" if it was.
+#[gm_func]
+pub fn __crystal_configure(key: &str, value: &str) -> String {
+    #[cfg(feature = "debug")]
+    println!("configure({key:?}, {value:?})");
+    configure(key, value).err().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalid_settings_are_reported() {
+        assert_eq!(
+            configure("worker_thread", "4").unwrap_err(),
+            "unknown key \"worker_thread\""
+        );
+        assert_eq!(
+            configure("runtime", "single").unwrap_err(),
+            "invalid value \"single\" for runtime"
+        );
+        for (key, value) in [
+            ("worker_threads", "0"),
+            ("worker_threads", "-2"),
+            ("log_level", "loud"),
+            ("notification_limit", "1.5"),
+            ("notification_policy", "3"),
+            ("call_timeout", "-1"),
+            ("call_timeout", "NaN"),
+            ("shutdown_timeout", "soon"),
+        ] {
+            assert!(configure(key, value).is_err(), "{key} = {value}");
+        }
+        if !cfg!(feature = "manual-time") {
+            assert_eq!(
+                configure("runtime", "manual").unwrap_err(),
+                "runtime manual needs the manual-time feature"
+            );
+        }
+    }
+
+    #[cfg(feature = "manual-time")]
+    #[test]
+    fn the_manual_clock_only_moves_when_advanced() {
+        let runtime = Builder::new_current_thread()
+            .enable_all()
+            .start_paused(true)
+            .build()
+            .unwrap();
+        let clock = ManualClock::hold(&runtime);
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        std::thread::spawn(move || {
+            std::thread::sleep(Duration::from_millis(50));
+            let _ = tx.send(());
+        });
+        runtime.block_on(async {
+            let start = Instant::now();
+            tokio::select! {
+                _ = rx => (),
+                _ = tokio::time::sleep(Duration::from_secs(1)) => panic!("the clock moved"),
+            }
+            assert_eq!(start.elapsed(), Duration::ZERO);
+            let timer = tokio::time::sleep(Duration::from_secs(1));
+            tokio::time::advance(Duration::from_secs(1)).await;
+            timer.await;
+        });
+        // Released, the clock jumps to the next timer again.
+        drop(clock);
+        runtime.block_on(async { tokio::time::sleep(Duration::from_secs(3600)).await });
+    }
+}
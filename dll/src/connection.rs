@@ -0,0 +1,279 @@
+//! Telling the game how a connection attempt went.
+//!
+//! The client only says whether it's connected, so once `__crystal_connect` gave up this module
+//! works out why by itself: if the websocket couldn't be opened, it looks the host up and
+//! connects to it over TCP to tell a DNS failure from a refused connection or a server which
+//! didn't accept the websocket. If the websocket opened but the client never got connected, the
+//! handshake failed. Once the game set its version, a client connected to a server which
+//! reports another one is a version mismatch, though it stays connected.
+//!
+//! Each attempt ends with a `connect_result` notification, see
+//! [`Notification::ConnectResult`]. One which connected is kept up as the [`crate::reconnect`]
+//! policy says.
+
+use std::{
+    fmt::Display,
+    sync::{Arc, atomic::Ordering},
+    time::Duration,
+};
+
+use futures_util::FutureExt;
+use gm_utils::gm_func;
+use tokio::{
+    net::{TcpStream, lookup_host},
+    time::timeout,
+};
+
+use crate::{
+    RUNTIME,
+    client::Client,
+    instance::{self, Instance},
+    notifications::{self, Notification, Notifier},
+};
+
+/// Where the client connects to, the one URL crystal-server 0.1.0 dials.
+const URL: &str = "ws://server.crystal-server.co:16562";
+
+/// How long the server gets to complete the handshake, and each probe to finish.
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Category {
+    Dns,
+    Refused,
+    Handshake,
+    Version,
+}
+
+impl Category {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Dns => "dns",
+            Self::Refused => "refused",
+            Self::Handshake => "handshake",
+            Self::Version => "version",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub(crate) struct ConnectError {
+    pub category: Category,
+    pub message: String,
+}
+
+impl ConnectError {
+    fn new(category: Category, message: impl Into<String>) -> Self {
+        Self {
+            category,
+            message: message.into(),
+        }
+    }
+}
+
+impl Display for ConnectError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.category.name(), self.message)
+    }
+}
+
+/// Splits a `ws://` or `wss://` URL into its host and port.
+fn endpoint(url: &str) -> Option<(&str, u16)> {
+    let (scheme, rest) = url.split_once("://")?;
+    let default_port = match scheme {
+        "ws" => 80,
+        "wss" => 443,
+        _ => return None,
+    };
+    let authority = rest.split(['/', '?']).next()?;
+    let (host, port) = match authority.rsplit_once(':') {
+        Some((host, port)) if !port.contains(']') => (host, port.parse().ok()?),
+        _ => (authority, default_port),
+    };
+    Some((host.trim_start_matches('[').trim_end_matches(']'), port))
+}
+
+/// Finds out why the websocket to `url` couldn't be opened.
+async fn probe(url: &str) -> ConnectError {
+    let Some((host, port)) = endpoint(url) else {
+        return ConnectError::new(Category::Dns, format!("{url} isn't a websocket URL"));
+    };
+    let addrs = match timeout(CONNECT_TIMEOUT, lookup_host((host, port))).await {
+        Ok(Ok(addrs)) => addrs.collect::<Vec<_>>(),
+        Ok(Err(err)) => return ConnectError::new(Category::Dns, err.to_string()),
+        Err(_) => return ConnectError::new(Category::Dns, format!("looking {host} up timed out")),
+    };
+    let mut refused = format!("{host} has no addresses");
+    for addr in addrs {
+        match timeout(CONNECT_TIMEOUT, TcpStream::connect(addr)).await {
+            Ok(Ok(_)) => {
+                return ConnectError::new(
+                    Category::Handshake,
+                    format!("{addr} accepted the connection, but not the websocket"),
+                );
+            }
+            Ok(Err(err)) => refused = format!("{addr}: {err}"),
+            Err(_) => refused = format!("connecting to {addr} timed out"),
+        }
+    }
+    ConnectError::new(Category::Refused, refused)
+}
+
+/// Connects the client of the instance the running export acts on, reporting the outcome.
+pub(crate) fn connect() {
+    let instance = instance::current();
+    instance.reconnect.stop();
+    let attempt = attempt(instance.clone(), notifications::notifier());
+    RUNTIME.get().spawn(async move {
+        if established(&attempt.await) {
+            instance.reconnect.established();
+        }
+    });
+}
+
+/// Starts connecting the client of `instance`, the returned future finishes the attempt and
+/// reports its outcome.
+pub(crate) fn attempt(
+    instance: Arc<Instance>,
+    notifier: Notifier,
+) -> impl Future<Output = Result<(), ConnectError>> + Send + 'static {
+    let client = instance.client.get();
+    // Boxed to end the borrow of the client, which the future doesn't need.
+    let opened = client
+        .run(|crystal| {
+            Box::pin(async move {
+                crystal.connect().await;
+                // Once connect returns, the websocket is either open or failed to.
+                crystal.is_connected().await || crystal.is_connecting().await
+            })
+        })
+        .boxed();
+    async move {
+        let result = match opened.await {
+            Ok(true) => handshake(&instance, &client).await,
+            // When the host a server redirected the client to can't be reached, the client
+            // forgets it and dials `URL` instead, so that's always the one which failed last.
+            Ok(false) => Err(probe(URL).await),
+            Err(err) => Err(ConnectError::new(Category::Handshake, err.to_string())),
+        };
+        *instance.connect_error.lock() = result.as_ref().err().map(ToString::to_string);
+        notifier.push(Notification::ConnectResult(result.clone()));
+        result
+    }
+}
+
+/// Whether the client is connected after an attempt ended with `result`.
+pub(crate) fn established(result: &Result<(), ConnectError>) -> bool {
+    matches!(
+        result,
+        Ok(())
+            | Err(ConnectError {
+                category: Category::Version,
+                ..
+            })
+    )
+}
+
+/// Waits until the client got connected or gave up, after opening the websocket.
+///
+/// The versions are only compared once the game set its own, a game which never did connects
+/// to any server.
+async fn handshake(instance: &Instance, client: &Arc<Client>) -> Result<(), ConnectError> {
+    let mut state = client.watch();
+    // What was published before the attempt finished doesn't tell anything about it.
+    state.borrow_and_update();
+    let connected = timeout(CONNECT_TIMEOUT, async {
+        loop {
+            state.changed().await.ok()?;
+            let state = state.borrow_and_update();
+            if state.is_connected || !state.is_connecting {
+                return Some(state.is_connected);
+            }
+        }
+    })
+    .await;
+    match connected {
+        Ok(Some(true)) => (),
+        Ok(Some(false)) | Ok(None) => {
+            return Err(ConnectError::new(
+                Category::Handshake,
+                "the server closed the connection during the handshake",
+            ));
+        }
+        Err(_) => {
+            return Err(ConnectError::new(
+                Category::Handshake,
+                "the server didn't complete the handshake in time",
+            ));
+        }
+    }
+    if !instance.has_version.load(Ordering::Relaxed) {
+        return Ok(());
+    }
+    let versions = client
+        .run(|crystal| {
+            Box::pin(async move {
+                (
+                    crystal.get_version().await,
+                    crystal.get_server_version().await,
+                )
+            })
+        })
+        .await;
+    match versions {
+        Ok((ours, theirs)) if ours != theirs => Err(ConnectError::new(
+            Category::Version,
+            format!("the game is at version {ours}, the server expects {theirs}"),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Why the last connection attempt failed, as `category: message`, or "" if it succeeded or
+/// none finished yet.
+#[gm_func]
+pub fn __crystal_get_last_connection_error() -> String {
+    #[cfg(feature = "debug")]
+    println!("get_last_connection_error()");
+    instance::current()
+        .connect_error
+        .lock()
+        .clone()
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn endpoints_are_split() {
+        assert_eq!(endpoint(URL), Some(("server.crystal-server.co", 16562)));
+        assert_eq!(endpoint("wss://example.com/ws"), Some(("example.com", 443)));
+        assert_eq!(endpoint("ws://[::1]:80"), Some(("::1", 80)));
+        assert_eq!(endpoint("ws://[::1]"), Some(("::1", 80)));
+        assert_eq!(endpoint("http://example.com"), None);
+        assert_eq!(endpoint("ws://example.com:port"), None);
+    }
+
+    #[test]
+    fn failures_are_categorized() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .unwrap();
+        runtime.block_on(async {
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let port = listener.local_addr().unwrap().port();
+            for (url, category) in [
+                (format!("ws://127.0.0.1:{port}"), Category::Handshake),
+                (String::from("ws://crystal.invalid"), Category::Dns),
+            ] {
+                assert_eq!(probe(&url).await.category, category, "{url}");
+            }
+            drop(listener);
+            let refused = probe(&format!("ws://127.0.0.1:{port}")).await;
+            assert_eq!(refused.category, Category::Refused, "{refused}");
+        });
+    }
+}
@@ -0,0 +1,379 @@
+//! Client instances, each with its own client task, notification queue and room.
+//!
+//! The exports without a handle act on the default instance, handle `0`, which always exists.
+//! `__crystal_client_create` adds another instance and returns its handle, which every
+//! `__crystal_client_*` form of those exports takes as its first argument, e.g.
+//! `__crystal_client_update(handle)` for `__crystal_update()`. Called with an unknown handle,
+//! they do nothing and return `0` or "".
+//!
+//! Settings made through `__crystal_configure` and the value and JSON encoding settings are
+//! shared by all instances, as is the runtime.
+
+use std::{
+    cell::Cell,
+    collections::HashMap,
+    sync::{
+        Arc, LazyLock,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+    },
+};
+
+use crystal_server::client::CrystalServer;
+use futures_util::future::BoxFuture;
+use gm_utils::{buffer::GmBuffer, gm_func};
+use parking_lot::{Mutex, RwLock};
+
+use crate::{
+    __crystal_init,
+    client::{CallPanicked, Client, Command},
+    connection,
+    lifecycle::Restartable,
+    notifications::{self, Queue},
+    reconnect::{self, Reconnect},
+    ticket,
+};
+
+pub(crate) struct Instance {
+    pub client: Restartable<Client>,
+    pub has_init: tokio::sync::Mutex<bool>,
+    pub room: Arc<RwLock<String>>,
+    pub queue: Arc<Mutex<Queue>>,
+    /// Why the last connection attempt failed.
+    pub connect_error: Mutex<Option<String>>,
+    /// Whether the game set its version, which is only checked from then on, see [`connection`].
+    pub has_version: AtomicBool,
+    /// When achievements were reached in the `manual` runtime, see [`crate::reach_achievement`].
+    pub reached: Arc<Mutex<HashMap<u64, i64>>>,
+    /// The update `__crystal_update_budget` ran out of time waiting for.
+    pub update: Mutex<Option<BoxFuture<'static, Result<bool, CallPanicked>>>>,
+    pub reconnect: Reconnect,
+}
+
+impl Instance {
+    fn new(handle: u64) -> Self {
+        Self {
+            client: Restartable::new(|| Client::spawn(CrystalServer::init(""))),
+            has_init: tokio::sync::Mutex::new(false),
+            room: Arc::new(RwLock::new(String::new())),
+            queue: Arc::new(Mutex::new(Queue::new(handle))),
+            connect_error: Mutex::new(None),
+            has_version: AtomicBool::new(false),
+            reached: Arc::new(Mutex::new(HashMap::new())),
+            update: Mutex::new(None),
+            reconnect: Reconnect::default(),
+        }
+    }
+}
+
+static INSTANCES: LazyLock<RwLock<HashMap<u64, Arc<Instance>>>> =
+    LazyLock::new(|| RwLock::new(HashMap::from([(0, Arc::new(Instance::new(0)))])));
+static NEXT_HANDLE: AtomicU64 = AtomicU64::new(1);
+
+thread_local! {
+    /// The instance exports act on, only set on the game's thread while a handle form runs.
+    static CURRENT: Cell<u64> = const { Cell::new(0) };
+}
+
+/// The instance the running export acts on.
+///
+/// Must be called from the export itself, not from a task or callback it starts, as those
+/// don't run within the handle form.
+pub(crate) fn current() -> Arc<Instance> {
+    let instances = INSTANCES.read();
+    instances
+        .get(&CURRENT.get())
+        .or_else(|| instances.get(&0))
+        .unwrap()
+        .clone()
+}
+
+/// The client of the instance the running export acts on, see [`current`].
+pub(crate) fn client() -> Arc<Client> {
+    current().client.get()
+}
+
+/// Every instance, in no particular order.
+pub(crate) fn all() -> Vec<Arc<Instance>> {
+    INSTANCES.read().values().cloned().collect()
+}
+
+fn to_handle(handle: f64) -> Option<u64> {
+    (handle >= 0.0 && handle.fract() == 0.0 && handle <= u64::MAX as f64).then_some(handle as u64)
+}
+
+/// Runs `f` acting on the instance `handle`, returns `None` if there's no such instance.
+fn with<R>(handle: f64, f: impl FnOnce() -> R) -> Option<R> {
+    struct Restore(u64);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            CURRENT.set(self.0);
+        }
+    }
+
+    let handle = to_handle(handle).filter(|handle| INSTANCES.read().contains_key(handle))?;
+    let _restore = Restore(CURRENT.replace(handle));
+    Some(f())
+}
+
+/// Runs `f` acting on a new, uninitialized instance that's destroyed afterwards.
+#[cfg(test)]
+pub(crate) fn scratch<R>(f: impl FnOnce() -> R) -> R {
+    let handle = NEXT_HANDLE.fetch_add(1, Ordering::Relaxed);
+    INSTANCES
+        .write()
+        .insert(handle, Arc::new(Instance::new(handle)));
+    let res = with(handle as f64, f);
+    INSTANCES.write().remove(&handle);
+    res.unwrap()
+}
+
+/// Creates a client instance, initialized with `game_id` like `__crystal_init`, and returns
+/// its handle.
+#[gm_func]
+pub fn __crystal_client_create(game_id: &str) -> f64 {
+    #[cfg(feature = "debug")]
+    println!("client_create({game_id:?})");
+    let handle = NEXT_HANDLE.fetch_add(1, Ordering::Relaxed);
+    INSTANCES
+        .write()
+        .insert(handle, Arc::new(Instance::new(handle)));
+    with(handle as f64, || __crystal_init(game_id));
+    handle as f64
+}
+
+/// Disconnects and removes a client instance, along with its queued notifications.
+///
+/// Returns false for unknown handles and the default instance, which can't be removed.
+#[gm_func]
+pub fn __crystal_client_destroy(handle: f64) -> bool {
+    #[cfg(feature = "debug")]
+    println!("client_destroy({handle:?})");
+    let Some(handle) = to_handle(handle).filter(|handle| *handle != 0) else {
+        return false;
+    };
+    let Some(instance) = INSTANCES.write().remove(&handle) else {
+        return false;
+    };
+    instance.reconnect.stop();
+    if let Some(client) = instance.client.take() {
+        // The client task stops once the disconnect ran and the last handle is gone.
+        client.send(Command::Run(Box::new(|crystal| {
+            Box::pin(async move { crystal.disconnect().await })
+        })));
+    }
+    true
+}
+
+/// Whether `handle` is the handle of an instance.
+#[gm_func]
+pub fn __crystal_client_exists(handle: f64) -> bool {
+    #[cfg(feature = "debug")]
+    println!("client_exists({handle:?})");
+    with(handle, || ()).is_some()
+}
+
+/// Defines the `__crystal_client_*` form of each export, taking the handle first.
+macro_rules! handle_forms {
+    ($($form:ident($($arg:ident: $ty:ty),*) $(-> $ret:ty)? => $export:path;)*) => {$(
+        #[gm_func]
+        pub fn $form(handle: f64, $($arg: $ty),*) $(-> $ret)? {
+            #[cfg(feature = "debug")]
+            println!("{}({handle:?}, ..)", stringify!($form).trim_start_matches("__crystal_"));
+            with(handle, || $export($($arg),*)).unwrap_or_default()
+        }
+    )*};
+}
+
+handle_forms! {
+    __crystal_client_set_room(room: &str) => crate::__crystal_set_room;
+    __crystal_client_init(game_id: &str) => crate::__crystal_init;
+    __crystal_client_connect() => crate::__crystal_connect;
+    __crystal_client_get_last_connection_error() -> String => connection::__crystal_get_last_connection_error;
+    __crystal_client_set_reconnect_policy(strategy: f64, delay: f64, max_delay: f64, max_attempts: f64) -> bool => reconnect::__crystal_set_reconnect_policy;
+    __crystal_client_update() -> bool => crate::__crystal_update;
+    __crystal_client_update_budget(budget: f64) -> bool => crate::__crystal_update_budget;
+    __crystal_client_is_connected() -> bool => crate::__crystal_is_connected;
+    __crystal_client_is_connecting() -> bool => crate::__crystal_is_connecting;
+    __crystal_client_is_loggedin() -> bool => crate::__crystal_is_loggedin;
+    __crystal_client_get_ping() -> f64 => crate::__crystal_get_ping;
+    __crystal_client_set_game_token(token: &str) => crate::__crystal_set_game_token;
+    __crystal_client_disconnect() => crate::__crystal_disconnect;
+    __crystal_client_login(name: &str, passw: &str) => crate::__crystal_login;
+    __crystal_client_login_with_token(name: &str, token: &str) => crate::__crystal_login_with_token;
+    __crystal_client_register(name: &str, email: &str, passw: &str, repeat_passw: &str) => crate::__crystal_register;
+    __crystal_client_get_player_id() -> f64 => crate::__crystal_get_player_id;
+    __crystal_client_get_player_name() -> String => crate::__crystal_get_player_name;
+    __crystal_client_set_variable(name: &str, variable: &str) => crate::__crystal_set_variable;
+    __crystal_client_remove_variable(name: &str) => crate::__crystal_remove_variable;
+    __crystal_client_iter_other_players() -> String => crate::__crystal_iter_other_players;
+    __crystal_client_other_player_count() -> f64 => crate::__crystal_other_player_count;
+    __crystal_client_get_other_player(pid: f64) -> String => crate::__crystal_get_other_player;
+    __crystal_client_get_other_player_name(name: &str) -> String => crate::__crystal_get_other_player_name;
+    __crystal_client_request_other_player_variable(pid: f64, name: &str, request: f64) => crate::__crystal_request_other_player_variable;
+    __crystal_client_p2p(target: f64, mid: f64, payload: &str) => crate::__crystal_p2p;
+    __crystal_client_set_version(version: f64) => crate::__crystal_set_version;
+    __crystal_client_get_version() -> f64 => crate::__crystal_get_version;
+    __crystal_client_get_server_version() -> f64 => crate::__crystal_get_server_version;
+    __crystal_client_set_session(session: &str) => crate::__crystal_set_session;
+    __crystal_client_get_session() -> String => crate::__crystal_get_session;
+    __crystal_client_get_open_playerini() -> String => crate::__crystal_get_open_playerini;
+    __crystal_client_open_playerini(file: &str) => crate::__crystal_open_playerini;
+    __crystal_client_close_playerini() => crate::__crystal_close_playerini;
+    __crystal_client_has_playerini(section: &str, key: &str) -> bool => crate::__crystal_has_playerini;
+    __crystal_client_get_playerini(section: &str, key: &str) -> String => crate::__crystal_get_playerini;
+    __crystal_client_set_playerini(section: &str, key: &str, vari: &str) => crate::__crystal_set_playerini;
+    __crystal_client_remove_playerini(section: &str, key: &str) => crate::__crystal_remove_playerini;
+    __crystal_client_get_open_gameini() -> String => crate::__crystal_get_open_gameini;
+    __crystal_client_open_gameini(file: &str) => crate::__crystal_open_gameini;
+    __crystal_client_close_gameini() => crate::__crystal_close_gameini;
+    __crystal_client_has_gameini(section: &str, key: &str) -> bool => crate::__crystal_has_gameini;
+    __crystal_client_get_gameini(section: &str, key: &str) -> String => crate::__crystal_get_gameini;
+    __crystal_client_set_gameini(section: &str, key: &str, vari: &str) => crate::__crystal_set_gameini;
+    __crystal_client_remove_gameini(section: &str, key: &str) => crate::__crystal_remove_gameini;
+    __crystal_client_has_achievement(aid: f64) -> bool => crate::__crystal_has_achievement;
+    __crystal_client_get_achievement(aid: f64) -> String => crate::__crystal_get_achievement;
+    __crystal_client_has_reached_achievement(aid: f64) -> bool => crate::__crystal_has_reached_achievement;
+    __crystal_client_get_reached_achievement(aid: f64) -> f64 => crate::__crystal_get_reached_achievement;
+    __crystal_client_reach_achievement(aid: f64) => crate::__crystal_reach_achievement;
+    __crystal_client_has_highscore(hid: f64) -> bool => crate::__crystal_has_highscore;
+    __crystal_client_get_highscore(hid: f64) -> String => crate::__crystal_get_highscore;
+    __crystal_client_has_score_highscore(hid: f64) -> bool => crate::__crystal_has_score_highscore;
+    __crystal_client_get_score_highscore(hid: f64) -> f64 => crate::__crystal_get_score_highscore;
+    __crystal_client_set_score_highscore(hid: f64, score: f64) => crate::__crystal_set_score_highscore;
+    __crystal_client_create_sync(sync_type: f64, kind: f64) -> f64 => crate::__crystal_create_sync;
+    __crystal_client_destroy_sync(sync: f64) => crate::__crystal_destroy_sync;
+    __crystal_client_set_variable_sync(sync: f64, name: &str, value: &str) => crate::__crystal_set_variable_sync;
+    __crystal_client_remove_variable_sync(sync: f64, name: &str) => crate::__crystal_remove_variable_sync;
+    __crystal_client_get_variable_other_sync(pid: f64, sync: f64, name: &str) -> String => crate::__crystal_get_variable_other_sync;
+    __crystal_client_iter_other_syncs() -> String => crate::__crystal_iter_other_syncs;
+    __crystal_client_is_player_admin(pid: f64) -> bool => crate::__crystal_is_player_admin;
+    __crystal_client_get_player_admin(pid: f64) -> String => crate::__crystal_get_player_admin;
+    __crystal_client_player_kick(pid: f64, reason: &str) -> bool => crate::__crystal_player_kick;
+    __crystal_client_player_ban(pid: f64, reason: &str, unban_time: f64) -> bool => crate::__crystal_player_ban;
+    __crystal_client_player_unban(pid: f64) -> bool => crate::__crystal_player_unban;
+    __crystal_client_logout() -> bool => crate::__crystal_logout;
+    __crystal_client_request_other_sync_variable(pid: f64, slot: f64, name: &str, request: f64) => crate::__crystal_request_other_sync_variable;
+    __crystal_client_fetch_bdb(name: &str) => crate::__crystal_fetch_bdb;
+    __crystal_client_set_bdb(name: &str, data: &str) => crate::__crystal_set_bdb;
+    __crystal_client_get_incoming_friends() -> String => crate::__crystal_get_incoming_friends;
+    __crystal_client_get_outgoing_friends() -> String => crate::__crystal_get_outgoing_friends;
+    __crystal_client_get_friends() -> String => crate::__crystal_get_friends;
+    __crystal_client_send_outgoing_friend(pid: f64) => crate::__crystal_send_outgoing_friend;
+    __crystal_client_remove_outgoing_friend(pid: f64) => crate::__crystal_remove_outgoing_friend;
+    __crystal_client_deny_incoming_friend(pid: f64) => crate::__crystal_deny_incoming_friend;
+    __crystal_client_accept_incoming_friend(pid: f64) => crate::__crystal_accept_incoming_friend;
+    __crystal_client_remove_friend(pid: f64) => crate::__crystal_remove_friend;
+    __crystal_client_update_async() -> f64 => ticket::__crystal_update_async;
+    __crystal_client_is_connected_async() -> f64 => ticket::__crystal_is_connected_async;
+    __crystal_client_is_connecting_async() -> f64 => ticket::__crystal_is_connecting_async;
+    __crystal_client_is_loggedin_async() -> f64 => ticket::__crystal_is_loggedin_async;
+    __crystal_client_get_ping_async() -> f64 => ticket::__crystal_get_ping_async;
+    __crystal_client_set_game_token_async(token: &str) -> f64 => ticket::__crystal_set_game_token_async;
+    __crystal_client_disconnect_async() -> f64 => ticket::__crystal_disconnect_async;
+    __crystal_client_login_async(name: &str, passw: &str) -> f64 => ticket::__crystal_login_async;
+    __crystal_client_login_with_token_async(name: &str, token: &str) -> f64 => ticket::__crystal_login_with_token_async;
+    __crystal_client_register_async(name: &str, email: &str, passw: &str, repeat_passw: &str) -> f64 => ticket::__crystal_register_async;
+    __crystal_client_get_player_id_async() -> f64 => ticket::__crystal_get_player_id_async;
+    __crystal_client_get_player_name_async() -> f64 => ticket::__crystal_get_player_name_async;
+    __crystal_client_set_variable_async(name: &str, variable: &str) -> f64 => ticket::__crystal_set_variable_async;
+    __crystal_client_remove_variable_async(name: &str) -> f64 => ticket::__crystal_remove_variable_async;
+    __crystal_client_iter_other_players_async() -> f64 => ticket::__crystal_iter_other_players_async;
+    __crystal_client_other_player_count_async() -> f64 => ticket::__crystal_other_player_count_async;
+    __crystal_client_get_other_player_async(pid: f64) -> f64 => ticket::__crystal_get_other_player_async;
+    __crystal_client_get_other_player_name_async(name: &str) -> f64 => ticket::__crystal_get_other_player_name_async;
+    __crystal_client_request_other_player_variable_async(pid: f64, name: &str, request: f64) -> f64 => ticket::__crystal_request_other_player_variable_async;
+    __crystal_client_p2p_async(target: f64, mid: f64, payload: &str) -> f64 => ticket::__crystal_p2p_async;
+    __crystal_client_set_version_async(version: f64) -> f64 => ticket::__crystal_set_version_async;
+    __crystal_client_get_version_async() -> f64 => ticket::__crystal_get_version_async;
+    __crystal_client_get_server_version_async() -> f64 => ticket::__crystal_get_server_version_async;
+    __crystal_client_set_session_async(session: &str) -> f64 => ticket::__crystal_set_session_async;
+    __crystal_client_get_session_async() -> f64 => ticket::__crystal_get_session_async;
+    __crystal_client_get_open_playerini_async() -> f64 => ticket::__crystal_get_open_playerini_async;
+    __crystal_client_open_playerini_async(file: &str) -> f64 => ticket::__crystal_open_playerini_async;
+    __crystal_client_close_playerini_async() -> f64 => ticket::__crystal_close_playerini_async;
+    __crystal_client_has_playerini_async(section: &str, key: &str) -> f64 => ticket::__crystal_has_playerini_async;
+    __crystal_client_get_playerini_async(section: &str, key: &str) -> f64 => ticket::__crystal_get_playerini_async;
+    __crystal_client_set_playerini_async(section: &str, key: &str, vari: &str) -> f64 => ticket::__crystal_set_playerini_async;
+    __crystal_client_remove_playerini_async(section: &str, key: &str) -> f64 => ticket::__crystal_remove_playerini_async;
+    __crystal_client_get_open_gameini_async() -> f64 => ticket::__crystal_get_open_gameini_async;
+    __crystal_client_open_gameini_async(file: &str) -> f64 => ticket::__crystal_open_gameini_async;
+    __crystal_client_close_gameini_async() -> f64 => ticket::__crystal_close_gameini_async;
+    __crystal_client_has_gameini_async(section: &str, key: &str) -> f64 => ticket::__crystal_has_gameini_async;
+    __crystal_client_get_gameini_async(section: &str, key: &str) -> f64 => ticket::__crystal_get_gameini_async;
+    __crystal_client_set_gameini_async(section: &str, key: &str, vari: &str) -> f64 => ticket::__crystal_set_gameini_async;
+    __crystal_client_remove_gameini_async(section: &str, key: &str) -> f64 => ticket::__crystal_remove_gameini_async;
+    __crystal_client_has_achievement_async(aid: f64) -> f64 => ticket::__crystal_has_achievement_async;
+    __crystal_client_get_achievement_async(aid: f64) -> f64 => ticket::__crystal_get_achievement_async;
+    __crystal_client_has_reached_achievement_async(aid: f64) -> f64 => ticket::__crystal_has_reached_achievement_async;
+    __crystal_client_get_reached_achievement_async(aid: f64) -> f64 => ticket::__crystal_get_reached_achievement_async;
+    __crystal_client_reach_achievement_async(aid: f64) -> f64 => ticket::__crystal_reach_achievement_async;
+    __crystal_client_has_highscore_async(hid: f64) -> f64 => ticket::__crystal_has_highscore_async;
+    __crystal_client_get_highscore_async(hid: f64) -> f64 => ticket::__crystal_get_highscore_async;
+    __crystal_client_has_score_highscore_async(hid: f64) -> f64 => ticket::__crystal_has_score_highscore_async;
+    __crystal_client_get_score_highscore_async(hid: f64) -> f64 => ticket::__crystal_get_score_highscore_async;
+    __crystal_client_set_score_highscore_async(hid: f64, score: f64) -> f64 => ticket::__crystal_set_score_highscore_async;
+    __crystal_client_create_sync_async(sync_type: f64, kind: f64) -> f64 => ticket::__crystal_create_sync_async;
+    __crystal_client_destroy_sync_async(sync: f64) -> f64 => ticket::__crystal_destroy_sync_async;
+    __crystal_client_set_variable_sync_async(sync: f64, name: &str, value: &str) -> f64 => ticket::__crystal_set_variable_sync_async;
+    __crystal_client_remove_variable_sync_async(sync: f64, name: &str) -> f64 => ticket::__crystal_remove_variable_sync_async;
+    __crystal_client_get_variable_other_sync_async(pid: f64, sync: f64, name: &str) -> f64 => ticket::__crystal_get_variable_other_sync_async;
+    __crystal_client_iter_other_syncs_async() -> f64 => ticket::__crystal_iter_other_syncs_async;
+    __crystal_client_is_player_admin_async(pid: f64) -> f64 => ticket::__crystal_is_player_admin_async;
+    __crystal_client_get_player_admin_async(pid: f64) -> f64 => ticket::__crystal_get_player_admin_async;
+    __crystal_client_player_kick_async(pid: f64, reason: &str) -> f64 => ticket::__crystal_player_kick_async;
+    __crystal_client_player_ban_async(pid: f64, reason: &str, unban_time: f64) -> f64 => ticket::__crystal_player_ban_async;
+    __crystal_client_player_unban_async(pid: f64) -> f64 => ticket::__crystal_player_unban_async;
+    __crystal_client_logout_async() -> f64 => ticket::__crystal_logout_async;
+    __crystal_client_request_other_sync_variable_async(pid: f64, slot: f64, name: &str, request: f64) -> f64 => ticket::__crystal_request_other_sync_variable_async;
+    __crystal_client_fetch_bdb_async(name: &str) -> f64 => ticket::__crystal_fetch_bdb_async;
+    __crystal_client_set_bdb_async(name: &str, data: &str) -> f64 => ticket::__crystal_set_bdb_async;
+    __crystal_client_get_incoming_friends_async() -> f64 => ticket::__crystal_get_incoming_friends_async;
+    __crystal_client_get_outgoing_friends_async() -> f64 => ticket::__crystal_get_outgoing_friends_async;
+    __crystal_client_get_friends_async() -> f64 => ticket::__crystal_get_friends_async;
+    __crystal_client_send_outgoing_friend_async(pid: f64) -> f64 => ticket::__crystal_send_outgoing_friend_async;
+    __crystal_client_remove_outgoing_friend_async(pid: f64) -> f64 => ticket::__crystal_remove_outgoing_friend_async;
+    __crystal_client_deny_incoming_friend_async(pid: f64) -> f64 => ticket::__crystal_deny_incoming_friend_async;
+    __crystal_client_accept_incoming_friend_async(pid: f64) -> f64 => ticket::__crystal_accept_incoming_friend_async;
+    __crystal_client_remove_friend_async(pid: f64) -> f64 => ticket::__crystal_remove_friend_async;
+    __crystal_client_set_subscriptions(kinds: &str) -> String => notifications::__crystal_set_subscriptions;
+    __crystal_client_get_subscriptions() -> String => notifications::__crystal_get_subscriptions;
+    __crystal_client_set_async_events(enabled: f64) -> bool => notifications::__crystal_set_async_events;
+    __crystal_client_get_notification() -> String => notifications::__crystal_get_notification;
+    __crystal_client_get_notifications() -> String => notifications::__crystal_get_notifications;
+    __crystal_client_drain_notifications(buffer: GmBuffer, size: f64) -> f64 => notifications::__crystal_drain_notifications;
+    __crystal_client_drain_notifications_binary(buffer: GmBuffer, size: f64) -> f64 => notifications::__crystal_drain_notifications_binary;
+    __crystal_client_get_notification_count() -> f64 => notifications::__crystal_get_notification_count;
+    __crystal_client_set_notification_limit(limit: f64, policy: f64) -> bool => notifications::__crystal_set_notification_limit;
+    __crystal_client_get_dropped_notifications() -> String => notifications::__crystal_get_dropped_notifications;
+    __crystal_client_set_coalescing(enabled: f64) => notifications::__crystal_set_coalescing;
+    __crystal_client_reset_dropped_notifications() => notifications::__crystal_reset_dropped_notifications;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn handles_select_their_instance() {
+        let handle = NEXT_HANDLE.fetch_add(1, Ordering::Relaxed);
+        INSTANCES
+            .write()
+            .insert(handle, Arc::new(Instance::new(handle)));
+        with(handle as f64, || {
+            *current().connect_error.lock() = Some(String::from("selected"))
+        })
+        .unwrap();
+        assert_eq!(CURRENT.get(), 0);
+        assert!(INSTANCES.read()[&handle].connect_error.lock().is_some());
+        assert!(current().connect_error.lock().is_none());
+
+        assert!(__crystal_client_destroy(handle as f64));
+        assert!(!__crystal_client_destroy(handle as f64));
+        assert!(!__crystal_client_destroy(0.0));
+        for handle in [handle as f64, 0.5, -1.0, f64::NAN] {
+            assert_eq!(with(handle, || ()), None, "{handle}");
+        }
+        assert_eq!(with(0.0, || ()), Some(()));
+    }
+}
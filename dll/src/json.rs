@@ -0,0 +1,179 @@
+//! Conversion between [`Value`]s and JSON, to exchange them with `json_parse`/`json_stringify`.
+//!
+//! | JSON                           | value                                                  |
+//! |--------------------------------|--------------------------------------------------------|
+//! | `null`                         | `Null`                                                 |
+//! | `true`, `false`                | `Bool`                                                 |
+//! | number without fraction or exponent which fits an `i64` | `Int`                         |
+//! | any other number               | `Float`                                                |
+//! | string                         | `String`                                               |
+//! | array                          | `Array`                                                |
+//! | object                         | `Struct`                                               |
+//!
+//! Going the other way, a `Float` is always written with a fraction or an exponent (`1.0`) so it
+//! stays a `Float`, except for NaN and the infinities which JSON can't hold and become `null`.
+//! A `Buffer` becomes the base64 string of its bytes, which comes back as a `String`.
+//!
+//! `json_stringify` writes whole reals without a fraction, so they arrive as an `Int`.
+//!
+//! Exports returning players, syncs, achievements, highscores, administrators or friends may
+//! return JSON instead of their own layout, see `__crystal_set_json_output`.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use base64::{Engine, prelude::BASE64_STANDARD};
+use crystal_server::types::Value;
+use gm_utils::gm_func;
+use serde_json::{Map, Number, Value as Json};
+
+use crate::value::{DecodeError, decode_vari, encode_vari, members, report};
+
+static OUTPUT: AtomicBool = AtomicBool::new(false);
+
+/// Whether structured exports return JSON.
+#[inline]
+pub(crate) fn output() -> bool {
+    OUTPUT.load(Ordering::Relaxed)
+}
+
+fn from_json(json: Json) -> Value {
+    match json {
+        Json::Null => Value::Null,
+        Json::Bool(val) => Value::Bool(val),
+        Json::Number(val) => match val.as_i64() {
+            Some(val) => Value::Int(val),
+            None => Value::Float(val.as_f64().unwrap_or(f64::NAN)),
+        },
+        Json::String(val) => Value::String(val),
+        Json::Array(val) => Value::Array(val.into_iter().map(from_json).collect()),
+        Json::Object(val) => Value::Struct(
+            val.into_iter()
+                .map(|(name, val)| (name, from_json(val)))
+                .collect(),
+        ),
+    }
+}
+
+pub(crate) fn to_json(value: &Value) -> Json {
+    match value {
+        Value::Null => Json::Null,
+        Value::Int(val) => Json::from(*val),
+        Value::Float(val) => Number::from_f64(*val).map_or(Json::Null, Json::Number),
+        Value::Bool(val) => Json::Bool(*val),
+        Value::String(val) => Json::String(val.clone()),
+        Value::Buffer(val) => Json::String(BASE64_STANDARD.encode(val)),
+        Value::Array(val) => Json::Array(val.iter().map(to_json).collect()),
+        Value::Struct(val) => Json::Object(
+            members(val)
+                .into_iter()
+                .map(|(name, val)| (name.clone(), to_json(val)))
+                .collect::<Map<_, _>>(),
+        ),
+    }
+}
+
+/// Parses a JSON document into a value.
+pub(crate) fn parse(s: &str) -> Result<Value, DecodeError> {
+    serde_json::from_str(s).map(from_json).map_err(|err| {
+        // serde_json reports a 1-based line and column, the column counting bytes.
+        let line_start = s
+            .split_inclusive('\n')
+            .take(err.line().saturating_sub(1))
+            .map(str::len)
+            .sum::<usize>();
+        let position = (line_start + err.column().saturating_sub(1)).min(s.len());
+        DecodeError::new(position, format!("invalid JSON ({err})"))
+    })
+}
+
+/// Converts a JSON document into an encoded value, or "" if it isn't valid JSON.
+#[gm_func]
+pub fn __crystal_json_to_value(json: &str) -> String {
+    #[cfg(feature = "debug")]
+    println!("json_to_value({json:?})");
+    report(parse(json))
+        .map(|value| encode_vari(&value))
+        .unwrap_or_default()
+}
+
+/// Converts an encoded value into JSON, or "" if it can't be decoded.
+#[gm_func]
+pub fn __crystal_value_to_json(value: &str) -> String {
+    #[cfg(feature = "debug")]
+    println!("value_to_json({value:?})");
+    let Ok(value) = decode_vari(value) else {
+        return String::new();
+    };
+    to_json(&value).to_string()
+}
+
+/// Toggles returning JSON from the exports which return players, syncs, achievements,
+/// highscores, administrators or friends, instead of their `:`-separated layouts.
+///
+/// Lists become arrays, missing items `null` and variables are converted as described above.
+/// Sent values and values returned alone, like by `__crystal_get_variable`, keep their encoding.
+#[gm_func]
+pub fn __crystal_set_json_output(enabled: f64) {
+    #[cfg(feature = "debug")]
+    println!("set_json_output({enabled:?})");
+    OUTPUT.store(enabled >= 0.5, Ordering::Relaxed);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    #[test]
+    fn mapping() {
+        let value = parse(
+            r#"{"a": [1, 1.0, 1e2, -9223372036854775808, 18446744073709551615],
+            "b": null, "c": true, "d": "é\u0000"}"#,
+        )
+        .unwrap();
+        let expected = Value::Struct(HashMap::from([
+            (
+                String::from("a"),
+                Value::Array(vec![
+                    Value::Int(1),
+                    Value::Float(1.0),
+                    Value::Float(100.0),
+                    Value::Int(i64::MIN),
+                    Value::Float(18446744073709551615.0),
+                ]),
+            ),
+            (String::from("b"), Value::Null),
+            (String::from("c"), Value::Bool(true)),
+            (String::from("d"), Value::String(String::from("é\0"))),
+        ]));
+        assert_eq!(value, expected);
+        assert_eq!(parse(&to_json(&value).to_string()).unwrap(), value);
+
+        let value = Value::Array(vec![
+            Value::Float(2.0),
+            Value::Float(f64::NAN),
+            Value::Buffer(vec![1, 2, 3]),
+        ]);
+        assert_eq!(to_json(&value).to_string(), r#"[2.0,null,"AQID"]"#);
+    }
+
+    #[test]
+    fn values_and_payloads_accept_json() {
+        assert_eq!(
+            decode_vari(r#"{"x": 1.5}"#).unwrap(),
+            Value::Struct(HashMap::from([(String::from("x"), Value::Float(1.5))]))
+        );
+        assert_eq!(
+            crate::value::decode_payload(r#"[1, "a"]"#).unwrap(),
+            vec![Value::Int(1), Value::String(String::from("a"))]
+        );
+    }
+
+    #[test]
+    fn errors_point_at_the_syntax_error() {
+        let err = parse("{\"a\": 1,\n \"b\" 2}").unwrap_err();
+        assert_eq!(err.position, 14);
+        assert!(err.reason.starts_with("invalid JSON"), "{err}");
+    }
+}
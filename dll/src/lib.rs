@@ -1,6 +1,7 @@
 use std::{
-    collections::{HashMap, VecDeque},
-    sync::LazyLock,
+    collections::HashMap,
+    sync::{Arc, atomic::Ordering},
+    time::Duration,
 };
 
 use base64::{Engine, prelude::BASE64_STANDARD};
@@ -8,213 +9,105 @@ use chrono::DateTime;
 use crystal_server::{
     client::CrystalServer,
     types::{
-        self, Achievement, AdminAction, Administrator, DataUpdate, Highscore, OptionalValue,
-        Player, PlayerRequest, SyncIter, SyncType, Value,
+        self, Achievement, Administrator, Highscore, Player, PlayerRequest, SyncIter, SyncType,
+        Value,
     },
 };
-use futures_util::{StreamExt, pin_mut};
+use futures_util::{FutureExt, StreamExt, pin_mut};
 use gm_utils::gm_func;
-use tokio::{runtime::Runtime, sync::Mutex};
+use parking_lot::Mutex;
+use serde_json::{Value as Json, json};
+use tokio::runtime::Runtime;
+
+use crate::{
+    instance::client,
+    lifecycle::Restartable,
+    notifications::Notification,
+    reconnect::Secret,
+    value::{decode_payload, decode_vari, encode_vari, members},
+};
+
+mod binary;
+mod client;
+mod config;
+mod connection;
+mod instance;
+mod json;
+mod lifecycle;
+mod notifications;
+mod reconnect;
+mod ticket;
+mod value;
+
+static RUNTIME: Restartable<Runtime> = Restartable::new(config::runtime);
 
-static CRYSTAL: LazyLock<Mutex<CrystalServer>> =
-    LazyLock::new(|| Mutex::new(CrystalServer::init("")));
-static RUNTIME: LazyLock<Runtime> = LazyLock::new(|| Runtime::new().unwrap());
-static NOTIFICATIONS: LazyLock<parking_lot::Mutex<VecDeque<String>>> =
-    LazyLock::new(|| parking_lot::Mutex::new(VecDeque::default()));
-static HAS_INIT: LazyLock<Mutex<bool>> = LazyLock::new(|| Mutex::new(false));
-static ROOM: LazyLock<parking_lot::RwLock<String>> =
-    LazyLock::new(|| parking_lot::RwLock::new(String::new()));
+#[cfg(fuzzing)]
+pub use value::fuzz_decode;
 
 #[gm_func]
 pub fn __crystal_set_room(room: &str) {
     #[cfg(feature = "debug")]
     println!("_set_room({room:?})");
-    RUNTIME.block_on(async {
-        *ROOM.write() = room.to_string();
+    let instance = instance::current();
+    RUNTIME.get().block_on(async {
+        *instance.room.write() = room.to_string();
     });
 }
 
+/// Sets the client up for `game_id`.
+///
+/// Calling it again starts over: the client disconnects and is set up anew, forgetting its
+/// callbacks, queued notifications and login, so it can switch to another game.
 #[gm_func]
 pub fn __crystal_init(game_id: &str) {
     #[cfg(feature = "debug")]
     println!("init({game_id:?})");
 
-    RUNTIME.block_on(async {
-        let mut hinit = HAS_INIT.lock().await;
-        if !*hinit {
-            *hinit = true;
-            drop(hinit);
-            tracing_subscriber::fmt().init();
-            let mut lock = CRYSTAL.lock().await;
-            *lock = CrystalServer::init(game_id);
-            lock.callback_set_room(Box::new(|| ROOM.read().clone()))
-                .await;
-            lock.callback_set_data_update(Box::new(|input| {
-                RUNTIME.spawn(async {
-                    NOTIFICATIONS.lock().push_back(match input {
-                        DataUpdate::AdminAction(aa) => match aa {
-                            AdminAction::Unban => String::from("admin_action;0"),
-                            AdminAction::Ban(reason, unban_time) => {
-                                format!(
-                                    "admin_action;1;{};{unban_time}",
-                                    BASE64_STANDARD.encode(reason)
-                                )
-                            }
-                            AdminAction::Kick(reason) => {
-                                format!("admin_action;2;{}", BASE64_STANDARD.encode(reason))
+    config::init_logging();
+    let instance = instance::current();
+    let game_id = game_id.to_owned();
+    let room = instance.room.clone();
+    let weak = Arc::downgrade(&instance);
+    let notifier = notifications::notifier();
+    RUNTIME.get().block_on(async {
+        let mut has_init = instance.has_init.lock().await;
+        let reinit = std::mem::replace(&mut *has_init, true);
+        instance.reconnect.reset();
+        let _ = instance
+            .client
+            .get()
+            .run(move |crystal| {
+                Box::pin(async move {
+                    if reinit {
+                        crystal.disconnect().await;
+                    }
+                    // Dropping the old client drops its callbacks too, so nothing it still
+                    // had queued can come after the notifications cleared below.
+                    *crystal = CrystalServer::init(&game_id);
+                    if reinit {
+                        notifier.clear();
+                    }
+                    crystal
+                        .callback_set_room(Box::new(move || room.read().clone()))
+                        .await;
+                    crystal
+                        .callback_set_data_update(Box::new(move |input| {
+                            let dropped = reconnect::observe(&weak, &input);
+                            if !reconnect::superseded(&weak, &input) {
+                                notifier.push(Notification::Data(input));
                             }
-                        },
-                        DataUpdate::Banned(reason, unban_time) => {
-                            format!(
-                                "banned;{};{}",
-                                BASE64_STANDARD.encode(reason),
-                                unban_time.timestamp()
-                            )
-                        }
-                        DataUpdate::ChangeFriendStatus(status) => {
-                            format!("friend_status;{status}")
-                        }
-                        DataUpdate::Disconnected() => String::from("disconnected"),
-                        DataUpdate::FetchBdb(name, value) => {
-                            if let Some(value) = value {
-                                format!(
-                                    "fetch_bdb;1;{};{}",
-                                    BASE64_STANDARD.encode(name),
-                                    BASE64_STANDARD.encode(value)
-                                )
-                            } else {
-                                format!("fetch_bdb;0;{}", BASE64_STANDARD.encode(name))
+                            if dropped {
+                                reconnect::start(&weak, &notifier);
                             }
-                        }
-                        DataUpdate::Kicked(reason) => {
-                            format!("kicked;{}", BASE64_STANDARD.encode(reason))
-                        }
-                        DataUpdate::Login(code) => {
-                            format!("login;{}", code as u64)
-                        }
-                        DataUpdate::LoginOk(pid, name) => {
-                            format!("login_ok;{pid};{}", BASE64_STANDARD.encode(name))
-                        }
-                        DataUpdate::LoginBan(code, reason, unban_time) => {
-                            format!(
-                                "login_ban;{};{};{unban_time}",
-                                code as u64,
-                                BASE64_STANDARD.encode(reason)
-                            )
-                        }
-                        DataUpdate::P2P(sender, mid, payload) => {
-                            format!(
-                                "p2p;{};{mid};{}",
-                                if let Some(sender) = sender {
-                                    sender.to_string()
-                                } else {
-                                    String::from("!")
-                                },
-                                encode_vari(&Value::Array(payload))
-                            )
-                        }
-                        DataUpdate::Registration(code) => {
-                            format!("register;{}", code as u64)
-                        }
-                        DataUpdate::PlayerLoggedIn(pid, name, room) => {
-                            format!(
-                                "player_logged_in;{pid};{};{}",
-                                BASE64_STANDARD.encode(name),
-                                BASE64_STANDARD.encode(room)
-                            )
-                        }
-                        DataUpdate::PlayerLoggedOut(pid) => {
-                            format!("player_logged_out;{pid}")
-                        }
-                        DataUpdate::Reconnecting() => String::from("reconnecting"),
-                        DataUpdate::ServerMessage(message) => {
-                            format!("server_message;{}", BASE64_STANDARD.encode(message))
-                        }
-                        DataUpdate::UpdateVariable(pid, name, value) => {
-                            format!(
-                                "update_variable;{pid};{};{}",
-                                BASE64_STANDARD.encode(name),
-                                if let OptionalValue::Some(value) = value {
-                                    encode_vari(&value)
-                                } else {
-                                    String::from("!!")
-                                }
-                            )
-                        }
-                        DataUpdate::UpdateSyncVariable(pid, slot, name, value) => {
-                            format!(
-                                "update_sync_variable;{pid};{slot};{};{}",
-                                BASE64_STANDARD.encode(name),
-                                if let OptionalValue::Some(value) = value {
-                                    encode_vari(&value)
-                                } else {
-                                    String::from("!!")
-                                }
-                            )
-                        }
-                        DataUpdate::UpdateSyncRemoval(pid, slot) => {
-                            format!("update_sync_removal;{pid};{slot}")
-                        }
-                        DataUpdate::UpdateGameIni(file, section, key, value) => {
-                            format!(
-                                "update_gameini;{};{};{};{}",
-                                if let Some(file) = file {
-                                    BASE64_STANDARD.encode(file)
-                                } else {
-                                    String::from("!!")
-                                },
-                                BASE64_STANDARD.encode(section),
-                                BASE64_STANDARD.encode(key),
-                                if let OptionalValue::Some(value) = value {
-                                    encode_vari(&value)
-                                } else {
-                                    String::from("!!")
-                                }
-                            )
-                        }
-                        DataUpdate::UpdatePlayerIni(file, section, key, value) => {
-                            format!(
-                                "update_playerini;{};{};{};{}",
-                                if let Some(file) = file {
-                                    BASE64_STANDARD.encode(file)
-                                } else {
-                                    String::from("!!")
-                                },
-                                BASE64_STANDARD.encode(section),
-                                BASE64_STANDARD.encode(key),
-                                if let OptionalValue::Some(value) = value {
-                                    encode_vari(&value)
-                                } else {
-                                    String::from("!!")
-                                }
-                            )
-                        }
-                        DataUpdate::UpdateGameVersion(ver) => {
-                            format!("update_gameversion;{ver}")
-                        }
-                        DataUpdate::UpdateAdministrator(pid, admin) => {
-                            format!(
-                                "update_administrator;{pid};{}",
-                                if let Some(admin) = admin {
-                                    format!(
-                                        "{}:{}:{}",
-                                        admin.can_ban, admin.can_unban, admin.can_kick
-                                    )
-                                } else {
-                                    String::from("!")
-                                }
-                            )
-                        }
-                        DataUpdate::ServerNotification(notif) => {
-                            format!("server_notification;{}", BASE64_STANDARD.encode(notif))
-                        }
-                        DataUpdate::LoginToken(token) => {
-                            format!("login_token;{}", BASE64_STANDARD.encode(token))
-                        }
-                    });
-                });
-            }))
+                        }))
+                        .await;
+                })
+            })
             .await;
+        if reinit {
+            instance.reached.lock().clear();
+            *instance.connect_error.lock() = None;
+            instance.has_version.store(false, Ordering::Relaxed);
         }
     });
 }
@@ -223,507 +116,696 @@ pub fn __crystal_init(game_id: &str) {
 pub fn __crystal_connect() {
     #[cfg(feature = "debug")]
     println!("connect()");
-    // TODO: This should probably be async, not blocking (sync.)
-    RUNTIME.spawn(async { CRYSTAL.lock().await.connect().await });
+    connection::connect();
 }
 
 #[gm_func]
 pub fn __crystal_update() -> bool {
     #[cfg(feature = "debug")]
     println!("update()");
-    RUNTIME.block_on(async { CRYSTAL.lock().await.update().await.is_ok() })
-}
-
-#[gm_func]
-pub fn __crystal_get_notification() -> String {
-    #[cfg(feature = "debug")]
-    println!("get_notification()");
-    NOTIFICATIONS.lock().pop_front().unwrap_or_default()
+    client()
+        .call(move |crystal| Box::pin(async move { crystal.update().await.is_ok() }))
+        .unwrap_or_default()
+}
+
+/// Like `__crystal_update`, but only waits `budget` microseconds for the update to finish.
+///
+/// An update still running by then keeps going in the background, and later calls wait for it
+/// instead of starting another one. Returns whether work is pending, i.e. the update didn't
+/// finish or notifications are waiting to be polled.
+#[gm_func]
+pub fn __crystal_update_budget(budget: f64) -> bool {
+    #[cfg(feature = "debug")]
+    println!("update_budget({budget:?})");
+    let budget =
+        Duration::try_from_secs_f64(budget.max(0.0) / 1_000_000.0).unwrap_or(Duration::MAX);
+    let instance = instance::current();
+    let mut update = instance.update.lock();
+    let running = update.get_or_insert_with(|| {
+        client()
+            .run(move |crystal| Box::pin(async move { crystal.update().await.is_ok() }))
+            .boxed()
+    });
+    let finished = RUNTIME
+        .get()
+        .block_on(tokio::time::timeout(budget, running))
+        .is_ok();
+    if finished {
+        *update = None;
+    }
+    !finished || notifications::queued() > 0
 }
 
 #[gm_func]
 pub fn __crystal_is_connected() -> bool {
     #[cfg(feature = "debug")]
     println!("is_connected()");
-    RUNTIME.block_on(async { CRYSTAL.lock().await.is_connected().await })
+    client().state().is_connected
 }
 
 #[gm_func]
 pub fn __crystal_is_connecting() -> bool {
     #[cfg(feature = "debug")]
     println!("is_connecting()");
-    RUNTIME.block_on(async { CRYSTAL.lock().await.is_connecting().await })
+    client().state().is_connecting
 }
 
 #[gm_func]
 pub fn __crystal_is_loggedin() -> bool {
     #[cfg(feature = "debug")]
     println!("is_loggedin()");
-    RUNTIME.block_on(async { CRYSTAL.lock().await.is_loggedin().await })
+    client().state().is_loggedin
 }
 
 #[gm_func]
 pub fn __crystal_get_ping() -> f64 {
     #[cfg(feature = "debug")]
     println!("get_ping()");
-    RUNTIME.block_on(async { CRYSTAL.lock().await.get_ping().await })
+    client().state().ping
 }
 
 #[gm_func]
 pub fn __crystal_set_game_token(token: &str) {
     #[cfg(feature = "debug")]
     println!("set_game_token({token:?})");
-    RUNTIME.block_on(async {
-        CRYSTAL.lock().await.set_game_token(token).await;
-    })
+    let token = token.to_owned();
+    client()
+        .call(move |crystal| {
+            Box::pin(async move {
+                crystal.set_game_token(&token).await;
+            })
+        })
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_disconnect() {
     #[cfg(feature = "debug")]
     println!("disconnect()");
-    RUNTIME.block_on(async {
-        CRYSTAL.lock().await.disconnect().await;
-    })
+    instance::current().reconnect.stop();
+    client()
+        .call(move |crystal| {
+            Box::pin(async move {
+                crystal.disconnect().await;
+            })
+        })
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_login(name: &str, passw: &str) {
     #[cfg(feature = "debug")]
     println!("login({name:?}, {passw:?})");
-    RUNTIME.block_on(async {
-        let _ = CRYSTAL.lock().await.login(name, passw).await;
-    })
+    instance::current()
+        .reconnect
+        .logging_in(name, Secret::Password(passw.to_owned()));
+    let (name, passw) = (name.to_owned(), passw.to_owned());
+    client()
+        .call(move |crystal| {
+            Box::pin(async move {
+                let _ = crystal.login(&name, &passw).await;
+            })
+        })
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_login_with_token(name: &str, token: &str) {
     #[cfg(feature = "debug")]
     println!("login_with_token({name:?}, {token:?})");
-    RUNTIME.block_on(async {
-        let _ = CRYSTAL.lock().await.login_with_token(name, token).await;
-    })
+    instance::current()
+        .reconnect
+        .logging_in(name, Secret::Token(token.to_owned()));
+    let (name, token) = (name.to_owned(), token.to_owned());
+    client()
+        .call(move |crystal| {
+            Box::pin(async move {
+                let _ = crystal.login_with_token(&name, &token).await;
+            })
+        })
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_register(name: &str, email: &str, passw: &str, repeat_passw: &str) {
     #[cfg(feature = "debug")]
     println!("register({name:?}, {email:?}, {passw:?}, {repeat_passw:?})");
-    RUNTIME.block_on(async {
-        let _ = CRYSTAL
-            .lock()
-            .await
-            .register(name, email, passw, repeat_passw)
-            .await;
-    })
+    let (name, email, passw, repeat_passw) = (
+        name.to_owned(),
+        email.to_owned(),
+        passw.to_owned(),
+        repeat_passw.to_owned(),
+    );
+    client()
+        .call(move |crystal| {
+            Box::pin(async move {
+                let _ = crystal.register(&name, &email, &passw, &repeat_passw).await;
+            })
+        })
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_get_player_id() -> f64 {
     #[cfg(feature = "debug")]
     println!("get_player_id()");
-    RUNTIME.block_on(async {
-        CRYSTAL
-            .lock()
-            .await
-            .get_player_id()
-            .await
-            .map(|val| val as f64)
-            .unwrap_or(-1.0)
-    })
+    client()
+        .state()
+        .player_id
+        .map(|val| val as f64)
+        .unwrap_or(-1.0)
 }
 
 #[gm_func]
 pub fn __crystal_get_player_name() -> String {
     #[cfg(feature = "debug")]
     println!("get_player_name()");
-    RUNTIME.block_on(async {
-        CRYSTAL
-            .lock()
-            .await
-            .get_player_name()
-            .await
-            .unwrap_or(String::new())
-    })
+    client().state().player_name.unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_set_variable(name: &str, variable: &str) {
     #[cfg(feature = "debug")]
     println!("set_variable({name:?}, {variable:?})");
-    RUNTIME.block_on(async {
-        CRYSTAL
-            .lock()
-            .await
-            .set_variable(name, decode_vari(variable))
-            .await;
-    })
+    let Ok(variable) = decode_vari(variable) else {
+        return;
+    };
+    let name = name.to_owned();
+    client()
+        .call(move |crystal| {
+            Box::pin(async move {
+                crystal.set_variable(&name, variable).await;
+            })
+        })
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_remove_variable(name: &str) {
     #[cfg(feature = "debug")]
     println!("remove_variable({name:?})");
-    RUNTIME.block_on(async {
-        CRYSTAL.lock().await.remove_variable(name).await;
-    })
+    let name = name.to_owned();
+    client()
+        .call(move |crystal| {
+            Box::pin(async move {
+                crystal.remove_variable(&name).await;
+            })
+        })
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_iter_other_players() -> String {
     #[cfg(feature = "debug")]
     println!("iter_other_players()");
-    RUNTIME.block_on(async {
-        let lock = CRYSTAL.lock().await;
-        let iter = lock.iter_other_players().await;
-        pin_mut!(iter);
-        let mut res = String::new();
-        while let Some((pid, player)) = iter.next().await {
-            if !res.is_empty() {
-                res.push(';');
-            }
-            res.push_str(&encode_player(pid, &player));
-        }
-        res
-    })
+    client()
+        .call(move |crystal| {
+            Box::pin(async move {
+                let lock = crystal;
+                let iter = lock.iter_other_players().await;
+                pin_mut!(iter);
+                let mut res = Vec::new();
+                while let Some((pid, player)) = iter.next().await {
+                    res.push(encode_player(pid, &player));
+                }
+                encode_list(res)
+            })
+        })
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_other_player_count() -> f64 {
     #[cfg(feature = "debug")]
     println!("other_player_count()");
-    RUNTIME.block_on(async { CRYSTAL.lock().await.other_player_count().await as f64 })
+    client()
+        .call(move |crystal| Box::pin(async move { crystal.other_player_count().await as f64 }))
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_get_other_player(pid: f64) -> String {
     #[cfg(feature = "debug")]
     println!("get_other_player({pid:?})");
-    RUNTIME.block_on(async {
-        if let Some(player) = CRYSTAL.lock().await.get_other_player(pid as u64).await {
-            encode_player(pid as u64, &player)
-        } else {
-            String::from("!")
-        }
-    })
+    client()
+        .call(move |crystal| {
+            Box::pin(async move {
+                if let Some(player) = crystal.get_other_player(pid as u64).await {
+                    encode_player(pid as u64, &player)
+                } else {
+                    encode_none()
+                }
+            })
+        })
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_get_other_player_name(name: &str) -> String {
     #[cfg(feature = "debug")]
     println!("get_other_player_name({name:?})");
-    RUNTIME.block_on(async {
-        if let Some((pid, player)) = CRYSTAL.lock().await.get_other_player_name(name).await {
-            encode_player(pid, &player)
-        } else {
-            String::from("!")
-        }
-    })
+    let name = name.to_owned();
+    client()
+        .call(move |crystal| {
+            Box::pin(async move {
+                if let Some((pid, player)) = crystal.get_other_player_name(&name).await {
+                    encode_player(pid, &player)
+                } else {
+                    encode_none()
+                }
+            })
+        })
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_request_other_player_variable(pid: f64, name: &str, request: f64) {
     #[cfg(feature = "debug")]
     println!("request_other_player_variable({pid:?}, {name:?}, {request:?})");
-    RUNTIME.block_on(async {
-        let _ = CRYSTAL
-            .lock()
-            .await
-            .request_other_player_variable(
-                pid as u64,
-                name,
-                Some(Box::new(move |pid, name, vari| {
-                    NOTIFICATIONS.lock().push_back(format!(
-                        "player_variable_request;{request};{pid};{name};{}",
-                        if let OptionalValue::Some(vari) = vari {
-                            encode_vari(&vari)
-                        } else {
-                            String::from("!")
-                        }
-                    ));
-                })),
-            )
-            .await;
-    })
+    let name = name.to_owned();
+    let notifier = notifications::notifier();
+    client()
+        .call(move |crystal| {
+            Box::pin(async move {
+                let _ = crystal
+                    .request_other_player_variable(
+                        pid as u64,
+                        &name,
+                        Some(Box::new(move |pid, name, vari| {
+                            notifier.push(Notification::PlayerVariableRequest {
+                                request,
+                                pid,
+                                name: name.to_string(),
+                                value: vari,
+                            });
+                        })),
+                    )
+                    .await;
+            })
+        })
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_p2p(target: f64, mid: f64, payload: &str) {
     #[cfg(feature = "debug")]
     println!("p2p({target:?}, {mid:?}, {payload:?})");
-    RUNTIME.block_on(async {
-        let _ = CRYSTAL
-            .lock()
-            .await
-            .p2p(
-                match target {
-                    -1.0 => PlayerRequest::AllGame,
-                    -2.0 => PlayerRequest::CurrentRoom,
-                    -3.0 => PlayerRequest::CurrentSession,
-                    -4.0 => PlayerRequest::Server,
-                    _ => PlayerRequest::ID(target as u64),
-                },
-                mid as i16,
-                {
-                    let ps = payload.to_string();
-                    let mut s = ps.split(";");
-                    let mut data = Vec::new();
-                    for _ in 0..s.next().unwrap().parse::<usize>().unwrap() {
-                        data.push(decode_vari(s.next().unwrap()));
-                    }
-                    data
-                },
-            )
-            .await;
-    })
+    let Ok(payload) = decode_payload(payload) else {
+        return;
+    };
+    client()
+        .call(move |crystal| {
+            Box::pin(async move {
+                let _ = crystal
+                    .p2p(decode_player_request(target), mid as i16, payload)
+                    .await;
+            })
+        })
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_set_version(version: f64) {
     #[cfg(feature = "debug")]
     println!("set_version({version:?})");
-    RUNTIME.block_on(async {
-        let _ = CRYSTAL.lock().await.set_version(version).await;
-    })
+    instance::current()
+        .has_version
+        .store(true, Ordering::Relaxed);
+    client()
+        .call(move |crystal| {
+            Box::pin(async move {
+                let _ = crystal.set_version(version).await;
+            })
+        })
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_get_version() -> f64 {
     #[cfg(feature = "debug")]
     println!("get_version()");
-    RUNTIME.block_on(async { CRYSTAL.lock().await.get_version().await })
+    client()
+        .call(move |crystal| Box::pin(async move { crystal.get_version().await }))
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_get_server_version() -> f64 {
     #[cfg(feature = "debug")]
     println!("get_server_version()");
-    RUNTIME.block_on(async { CRYSTAL.lock().await.get_server_version().await })
+    client()
+        .call(move |crystal| Box::pin(async move { crystal.get_server_version().await }))
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_set_session(session: &str) {
     #[cfg(feature = "debug")]
     println!("set_session({session:?})");
-    RUNTIME.block_on(async {
-        let _ = CRYSTAL.lock().await.set_session(session).await;
-    })
+    let session = session.to_owned();
+    client()
+        .call(move |crystal| {
+            Box::pin(async move {
+                let _ = crystal.set_session(&session).await;
+            })
+        })
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_get_session() -> String {
     #[cfg(feature = "debug")]
     println!("get_session()");
-    RUNTIME.block_on(async { CRYSTAL.lock().await.get_session().await })
+    client()
+        .call(move |crystal| Box::pin(async move { crystal.get_session().await }))
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_get_open_playerini() -> String {
     #[cfg(feature = "debug")]
     println!("get_open_playerini()");
-    RUNTIME.block_on(async { CRYSTAL.lock().await.get_open_playerini().await })
+    client()
+        .call(move |crystal| Box::pin(async move { crystal.get_open_playerini().await }))
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_open_playerini(file: &str) {
     #[cfg(feature = "debug")]
     println!("open_playerini({file:?})");
-    RUNTIME.block_on(async {
-        CRYSTAL.lock().await.open_playerini(file).await;
-    })
+    let file = file.to_owned();
+    client()
+        .call(move |crystal| {
+            Box::pin(async move {
+                crystal.open_playerini(&file).await;
+            })
+        })
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_close_playerini() {
     #[cfg(feature = "debug")]
     println!("close_playerini()");
-    RUNTIME.block_on(async {
-        CRYSTAL.lock().await.close_playerini().await;
-    })
+    client()
+        .call(move |crystal| {
+            Box::pin(async move {
+                crystal.close_playerini().await;
+            })
+        })
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_has_playerini(section: &str, key: &str) -> bool {
     #[cfg(feature = "debug")]
     println!("has_playerini({section:?}, {key:?})");
-    RUNTIME.block_on(async { CRYSTAL.lock().await.has_playerini(section, key).await })
+    let (section, key) = (section.to_owned(), key.to_owned());
+    client()
+        .call(move |crystal| Box::pin(async move { crystal.has_playerini(&section, &key).await }))
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_get_playerini(section: &str, key: &str) -> String {
     #[cfg(feature = "debug")]
     println!("get_playerini({section:?}, {key:?})");
-    RUNTIME.block_on(async {
-        if let Some(vari) = CRYSTAL.lock().await.get_playerini(section, key).await {
-            encode_vari(&vari)
-        } else {
-            String::from("!")
-        }
-    })
+    let (section, key) = (section.to_owned(), key.to_owned());
+    client()
+        .call(move |crystal| {
+            Box::pin(async move {
+                if let Some(vari) = crystal.get_playerini(&section, &key).await {
+                    encode_vari(&vari)
+                } else {
+                    String::from("!")
+                }
+            })
+        })
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_set_playerini(section: &str, key: &str, vari: &str) {
     #[cfg(feature = "debug")]
     println!("set_playerini({section:?}, {key:?}, {vari:?})");
-    RUNTIME.block_on(async {
-        CRYSTAL
-            .lock()
-            .await
-            .set_playerini(section, key, decode_vari(vari))
-            .await;
-    })
+    let Ok(vari) = decode_vari(vari) else {
+        return;
+    };
+    let (section, key) = (section.to_owned(), key.to_owned());
+    client()
+        .call(move |crystal| {
+            Box::pin(async move {
+                crystal.set_playerini(&section, &key, vari).await;
+            })
+        })
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_remove_playerini(section: &str, key: &str) {
     #[cfg(feature = "debug")]
     println!("remove_playerini({section:?}, {key:?})");
-    RUNTIME.block_on(async {
-        CRYSTAL.lock().await.remove_playerini(section, key).await;
-    })
+    let (section, key) = (section.to_owned(), key.to_owned());
+    client()
+        .call(move |crystal| {
+            Box::pin(async move {
+                crystal.remove_playerini(&section, &key).await;
+            })
+        })
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_get_open_gameini() -> String {
     #[cfg(feature = "debug")]
     println!("get_open_gameini()");
-    RUNTIME.block_on(async { CRYSTAL.lock().await.get_open_gameini().await })
+    client()
+        .call(move |crystal| Box::pin(async move { crystal.get_open_gameini().await }))
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_open_gameini(file: &str) {
     #[cfg(feature = "debug")]
     println!("open_gameini({file:?})");
-    RUNTIME.block_on(async {
-        CRYSTAL.lock().await.open_gameini(file).await;
-    })
+    let file = file.to_owned();
+    client()
+        .call(move |crystal| {
+            Box::pin(async move {
+                crystal.open_gameini(&file).await;
+            })
+        })
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_close_gameini() {
     #[cfg(feature = "debug")]
     println!("close_gameini()");
-    RUNTIME.block_on(async {
-        CRYSTAL.lock().await.close_gameini().await;
-    })
+    client()
+        .call(move |crystal| {
+            Box::pin(async move {
+                crystal.close_gameini().await;
+            })
+        })
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_has_gameini(section: &str, key: &str) -> bool {
     #[cfg(feature = "debug")]
     println!("has_gameini({section:?}, {key:?})");
-    RUNTIME.block_on(async { CRYSTAL.lock().await.has_gameini(section, key).await })
+    let (section, key) = (section.to_owned(), key.to_owned());
+    client()
+        .call(move |crystal| Box::pin(async move { crystal.has_gameini(&section, &key).await }))
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_get_gameini(section: &str, key: &str) -> String {
     #[cfg(feature = "debug")]
     println!("get_gameini({section:?}, {key:?})");
-    RUNTIME.block_on(async {
-        if let Some(vari) = CRYSTAL.lock().await.get_gameini(section, key).await {
-            encode_vari(&vari)
-        } else {
-            String::from("!")
-        }
-    })
+    let (section, key) = (section.to_owned(), key.to_owned());
+    client()
+        .call(move |crystal| {
+            Box::pin(async move {
+                if let Some(vari) = crystal.get_gameini(&section, &key).await {
+                    encode_vari(&vari)
+                } else {
+                    String::from("!")
+                }
+            })
+        })
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_set_gameini(section: &str, key: &str, vari: &str) {
     #[cfg(feature = "debug")]
     println!("set_gameini({section:?}, {key:?}, {vari:?})");
-    RUNTIME.block_on(async {
-        CRYSTAL
-            .lock()
-            .await
-            .set_gameini(section, key, decode_vari(vari))
-            .await;
-    })
+    let Ok(vari) = decode_vari(vari) else {
+        return;
+    };
+    let (section, key) = (section.to_owned(), key.to_owned());
+    client()
+        .call(move |crystal| {
+            Box::pin(async move {
+                crystal.set_gameini(&section, &key, vari).await;
+            })
+        })
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_remove_gameini(section: &str, key: &str) {
     #[cfg(feature = "debug")]
     println!("remove_gameini({section:?}, {key:?})");
-    RUNTIME.block_on(async {
-        CRYSTAL.lock().await.remove_gameini(section, key).await;
-    })
+    let (section, key) = (section.to_owned(), key.to_owned());
+    client()
+        .call(move |crystal| {
+            Box::pin(async move {
+                crystal.remove_gameini(&section, &key).await;
+            })
+        })
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_has_achievement(aid: f64) -> bool {
     #[cfg(feature = "debug")]
     println!("has_achievement({aid:?})");
-    RUNTIME.block_on(async { CRYSTAL.lock().await.has_achievement(aid as u64).await })
+    client()
+        .call(move |crystal| Box::pin(async move { crystal.has_achievement(aid as u64).await }))
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_get_achievement(aid: f64) -> String {
     #[cfg(feature = "debug")]
     println!("get_achievement({aid:?})");
-    RUNTIME.block_on(async {
-        encode_achievement(&CRYSTAL.lock().await.get_achievement(aid as u64).await)
-    })
+    let reached = instance::current().reached.clone();
+    client()
+        .call(move |crystal| {
+            Box::pin(async move {
+                encode_achievement(&get_achievement(crystal, &reached, aid as u64).await)
+            })
+        })
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_has_reached_achievement(aid: f64) -> bool {
     #[cfg(feature = "debug")]
     println!("has_reached_achievement({aid:?})");
-    RUNTIME.block_on(async {
-        CRYSTAL
-            .lock()
-            .await
-            .has_reached_achievement(aid as u64)
-            .await
-    })
+    client()
+        .call(move |crystal| {
+            Box::pin(async move { crystal.has_reached_achievement(aid as u64).await })
+        })
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_get_reached_achievement(aid: f64) -> f64 {
     #[cfg(feature = "debug")]
     println!("get_reached_achievement({aid:?})");
-    RUNTIME.block_on(async {
-        CRYSTAL
-            .lock()
-            .await
-            .get_reached_achievement(aid as u64)
-            .await
-            .map(|val| val as f64)
-            .unwrap_or(f64::NAN)
-    })
+    let reached = instance::current().reached.clone();
+    client()
+        .call(move |crystal| {
+            Box::pin(async move {
+                get_reached_achievement(crystal, &reached, aid as u64)
+                    .await
+                    .map(|val| val as f64)
+                    .unwrap_or(f64::NAN)
+            })
+        })
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_reach_achievement(aid: f64) {
     #[cfg(feature = "debug")]
     println!("reach_achievement({aid:?})");
-    RUNTIME.block_on(async {
-        let _ = CRYSTAL.lock().await.reach_achievement(aid as u64).await;
-    })
+    let reached = instance::current().reached.clone();
+    client()
+        .call(move |crystal| {
+            Box::pin(async move {
+                let _ = reach_achievement(crystal, &reached, aid as u64).await;
+            })
+        })
+        .unwrap_or_default()
+}
+
+/// Reaches achievement `aid` like the client does, and in the `manual` runtime notes when by
+/// its clock in `reached`, as the client stamps it with the wall clock.
+pub(crate) async fn reach_achievement(
+    crystal: &mut CrystalServer,
+    reached: &Mutex<HashMap<u64, i64>>,
+    aid: u64,
+) -> std::io::Result<()> {
+    let had_reached = crystal.has_reached_achievement(aid).await;
+    crystal.reach_achievement(aid).await?;
+    if let Some(now) = config::manual_time()
+        && !had_reached
+        && crystal.has_reached_achievement(aid).await
+    {
+        reached.lock().insert(aid, now);
+    }
+    Ok(())
+}
+
+/// When achievement `aid` was reached, see [`reach_achievement`].
+pub(crate) async fn get_reached_achievement(
+    crystal: &CrystalServer,
+    reached: &Mutex<HashMap<u64, i64>>,
+    aid: u64,
+) -> Option<i64> {
+    let unlocked = crystal.get_reached_achievement(aid).await?;
+    Some(reached.lock().get(&aid).copied().unwrap_or(unlocked))
+}
+
+/// Achievement `aid`, stamped like [`get_reached_achievement`] does.
+pub(crate) async fn get_achievement(
+    crystal: &CrystalServer,
+    reached: &Mutex<HashMap<u64, i64>>,
+    aid: u64,
+) -> Option<Achievement> {
+    let mut achievement = crystal.get_achievement(aid).await?;
+    if let Some(unlocked) = &mut achievement.unlocked
+        && let Some(at) = reached.lock().get(&aid)
+    {
+        *unlocked = *at;
+    }
+    Some(achievement)
 }
 
 #[gm_func]
 pub fn __crystal_has_highscore(hid: f64) -> bool {
     #[cfg(feature = "debug")]
     println!("has_highscore({hid:?})");
-    RUNTIME.block_on(async { CRYSTAL.lock().await.has_highscore(hid as u64).await })
+    client()
+        .call(move |crystal| Box::pin(async move { crystal.has_highscore(hid as u64).await }))
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_get_highscore(hid: f64) -> String {
     #[cfg(feature = "debug")]
     println!("get_highscore({hid:?})");
-    RUNTIME
-        .block_on(async { encode_highscore(&CRYSTAL.lock().await.get_highscore(hid as u64).await) })
+    client()
+        .call(move |crystal| {
+            Box::pin(async move { encode_highscore(&crystal.get_highscore(hid as u64).await) })
+        })
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_has_score_highscore(hid: f64) -> bool {
     #[cfg(feature = "debug")]
     println!("has_score_highscore({hid:?})");
-    RUNTIME.block_on(async { CRYSTAL.lock().await.has_score_highscore(hid as u64).await })
+    client()
+        .call(move |crystal| Box::pin(async move { crystal.has_score_highscore(hid as u64).await }))
+        .unwrap_or_default()
 }
 
 #[gm_func]
@@ -731,335 +813,433 @@ pub fn __crystal_get_score_highscore(hid: f64) -> f64 {
     #[cfg(feature = "debug")]
     println!("get_score_highscore({hid:?})");
 
-    RUNTIME.block_on(async {
-        CRYSTAL
-            .lock()
-            .await
-            .get_score_highscore(hid as u64)
-            .await
-            .unwrap_or(f64::NAN)
-    })
+    client()
+        .call(move |crystal| {
+            Box::pin(async move {
+                crystal
+                    .get_score_highscore(hid as u64)
+                    .await
+                    .unwrap_or(f64::NAN)
+            })
+        })
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_set_score_highscore(hid: f64, score: f64) {
     #[cfg(feature = "debug")]
     println!("set_score_highscore({hid:?}, {score:?})");
-    RUNTIME.block_on(async {
-        let _ = CRYSTAL
-            .lock()
-            .await
-            .set_score_highscore(hid as u64, score)
-            .await;
-    })
+    client()
+        .call(move |crystal| {
+            Box::pin(async move {
+                let _ = crystal.set_score_highscore(hid as u64, score).await;
+            })
+        })
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_create_sync(sync_type: f64, kind: f64) -> f64 {
     #[cfg(feature = "debug")]
     println!("create_sync({sync_type:?}, {kind:?})");
-    RUNTIME.block_on(async {
-        CRYSTAL
-            .lock()
-            .await
-            .create_sync(SyncType::try_from(sync_type as u8).unwrap(), kind as i16)
-            .await as f64
-    })
+    client()
+        .call(move |crystal| {
+            Box::pin(async move {
+                crystal
+                    .create_sync(SyncType::try_from(sync_type as u8).unwrap(), kind as i16)
+                    .await as f64
+            })
+        })
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_destroy_sync(sync: f64) {
     #[cfg(feature = "debug")]
     println!("destroy_sync({sync:?})");
-    RUNTIME.block_on(async {
-        let _ = CRYSTAL.lock().await.destroy_sync(sync as usize).await;
-    })
+    client()
+        .call(move |crystal| {
+            Box::pin(async move {
+                let _ = crystal.destroy_sync(sync as usize).await;
+            })
+        })
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_set_variable_sync(sync: f64, name: &str, value: &str) {
     #[cfg(feature = "debug")]
     println!("set_variable_sync({sync:?}, {name:?}, {value:?})");
-    RUNTIME.block_on(async {
-        let _ = CRYSTAL
-            .lock()
-            .await
-            .set_variable_sync(sync as usize, name, decode_vari(value))
-            .await;
-    })
+    let Ok(value) = decode_vari(value) else {
+        return;
+    };
+    let name = name.to_owned();
+    client()
+        .call(move |crystal| {
+            Box::pin(async move {
+                let _ = crystal.set_variable_sync(sync as usize, &name, value).await;
+            })
+        })
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_remove_variable_sync(sync: f64, name: &str) {
     #[cfg(feature = "debug")]
     println!("remove_variable_sync({sync:?}, {name:?})");
-    RUNTIME.block_on(async {
-        let _ = CRYSTAL
-            .lock()
-            .await
-            .remove_variable_sync(sync as usize, name)
-            .await;
-    })
+    let name = name.to_owned();
+    client()
+        .call(move |crystal| {
+            Box::pin(async move {
+                let _ = crystal.remove_variable_sync(sync as usize, &name).await;
+            })
+        })
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_get_variable_other_sync(pid: f64, sync: f64, name: &str) -> String {
     #[cfg(feature = "debug")]
     println!("get_variable_other_sync({pid:?}, {sync:?}, {name:?})");
-    RUNTIME.block_on(async {
-        if let Some(vari) = CRYSTAL
-            .lock()
-            .await
-            .get_variable_other_sync(pid as u64, sync as usize, name)
-            .await
-        {
-            encode_vari(&vari)
-        } else {
-            String::from("!")
-        }
-    })
+    let name = name.to_owned();
+    client()
+        .call(move |crystal| {
+            Box::pin(async move {
+                if let Some(vari) = crystal
+                    .get_variable_other_sync(pid as u64, sync as usize, &name)
+                    .await
+                {
+                    encode_vari(&vari)
+                } else {
+                    String::from("!")
+                }
+            })
+        })
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_iter_other_syncs() -> String {
     #[cfg(feature = "debug")]
     println!("iter_other_syncs()");
-    RUNTIME.block_on(async {
-        let lock = CRYSTAL.lock().await;
-        let iter = lock.iter_other_syncs().await;
-        pin_mut!(iter);
-        let mut res = String::new();
-        while let Some(sync) = iter.next().await {
-            if !res.is_empty() {
-                res.push(';');
-            }
-            res.push_str(&encode_synciter(&sync));
-        }
-        res
-    })
+    client()
+        .call(move |crystal| {
+            Box::pin(async move {
+                let lock = crystal;
+                let iter = lock.iter_other_syncs().await;
+                pin_mut!(iter);
+                let mut res = Vec::new();
+                while let Some(sync) = iter.next().await {
+                    res.push(encode_synciter(&sync));
+                }
+                encode_list(res)
+            })
+        })
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_is_player_admin(pid: f64) -> bool {
     #[cfg(feature = "debug")]
     println!("is_player_admin({pid:?})");
-    RUNTIME.block_on(async { CRYSTAL.lock().await.is_player_admin(pid as u64).await })
+    client()
+        .call(move |crystal| Box::pin(async move { crystal.is_player_admin(pid as u64).await }))
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_get_player_admin(pid: f64) -> String {
     #[cfg(feature = "debug")]
     println!("get_player_admin({pid:?})");
-    RUNTIME.block_on(async {
-        if let Some(admin) = CRYSTAL.lock().await.get_player_admin(pid as u64).await {
-            encode_administrator(&admin)
-        } else {
-            String::from("!")
-        }
-    })
+    client()
+        .call(move |crystal| {
+            Box::pin(async move {
+                if let Some(admin) = crystal.get_player_admin(pid as u64).await {
+                    encode_administrator(&admin)
+                } else {
+                    encode_none()
+                }
+            })
+        })
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_player_kick(pid: f64, reason: &str) -> bool {
     #[cfg(feature = "debug")]
     println!("player_kick({pid:?}, {reason:?})");
-    RUNTIME.block_on(async {
-        CRYSTAL
-            .lock()
-            .await
-            .player_kick(pid as u64, reason)
-            .await
-            .unwrap()
-    })
+    let reason = reason.to_owned();
+    client()
+        .call(move |crystal| {
+            Box::pin(async move { crystal.player_kick(pid as u64, &reason).await.unwrap() })
+        })
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_player_ban(pid: f64, reason: &str, unban_time: f64) -> bool {
     #[cfg(feature = "debug")]
     println!("player_ban({pid:?}, {reason:?}, {unban_time:?})");
-    RUNTIME.block_on(async {
-        CRYSTAL
-            .lock()
-            .await
-            .player_ban(
-                pid as u64,
-                reason,
-                DateTime::from_timestamp(unban_time as i64, 0).unwrap(),
-            )
-            .await
-            .unwrap()
-    })
+    let reason = reason.to_owned();
+    client()
+        .call(move |crystal| {
+            Box::pin(async move {
+                crystal
+                    .player_ban(
+                        pid as u64,
+                        &reason,
+                        DateTime::from_timestamp(unban_time as i64, 0).unwrap(),
+                    )
+                    .await
+                    .unwrap()
+            })
+        })
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_player_unban(pid: f64) -> bool {
     #[cfg(feature = "debug")]
     println!("player_unban({pid:?})");
-    RUNTIME.block_on(async { CRYSTAL.lock().await.player_unban(pid as u64).await.unwrap() })
+    client()
+        .call(move |crystal| {
+            Box::pin(async move { crystal.player_unban(pid as u64).await.unwrap() })
+        })
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_logout() -> bool {
     #[cfg(feature = "debug")]
     println!("logout()");
-    RUNTIME.block_on(async { CRYSTAL.lock().await.logout().await.unwrap() })
+    instance::current().reconnect.forget_login();
+    client()
+        .call(move |crystal| Box::pin(async move { crystal.logout().await.unwrap() }))
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_request_other_sync_variable(pid: f64, slot: f64, name: &str, request: f64) {
     #[cfg(feature = "debug")]
     println!("request_other_sync_variable({pid:?}, {slot:?}, {name:?}, {request:?})");
-    RUNTIME.block_on(async {
-        let _ = CRYSTAL
-            .lock()
-            .await
-            .request_other_sync_variable(
-                pid as u64,
-                slot as usize,
-                name,
-                Some(Box::new(move |pid, name, vari| {
-                    NOTIFICATIONS.lock().push_back(format!(
-                        "sync_variable_request;{request};{pid};{name};{}",
-                        if let OptionalValue::Some(vari) = vari {
-                            encode_vari(&vari)
-                        } else {
-                            String::from("!")
-                        }
-                    ));
-                })),
-            )
-            .await;
-    })
+    let name = name.to_owned();
+    let notifier = notifications::notifier();
+    client()
+        .call(move |crystal| {
+            Box::pin(async move {
+                let _ = crystal
+                    .request_other_sync_variable(
+                        pid as u64,
+                        slot as usize,
+                        &name,
+                        Some(Box::new(move |pid, name, vari| {
+                            notifier.push(Notification::SyncVariableRequest {
+                                request,
+                                pid,
+                                name: name.to_string(),
+                                value: vari,
+                            });
+                        })),
+                    )
+                    .await;
+            })
+        })
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_fetch_bdb(name: &str) {
     #[cfg(feature = "debug")]
-    println!("fetch_bdb({name:?}, {request:?})");
-    RUNTIME.block_on(async {
-        let _ = CRYSTAL.lock().await.fetch_bdb(name, None).await;
-    })
+    println!("fetch_bdb({name:?})");
+    let name = name.to_owned();
+    client()
+        .call(move |crystal| {
+            Box::pin(async move {
+                let _ = crystal.fetch_bdb(&name, None).await;
+            })
+        })
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_set_bdb(name: &str, data: &str) {
     #[cfg(feature = "debug")]
     println!("set_bdb({name:?}, {data:?})");
-    RUNTIME.block_on(async {
-        let _ = CRYSTAL
-            .lock()
-            .await
-            .set_bdb(name, BASE64_STANDARD.decode(data).unwrap())
-            .await;
-    })
+    let (name, data) = (name.to_owned(), data.to_owned());
+    client()
+        .call(move |crystal| {
+            Box::pin(async move {
+                let _ = crystal
+                    .set_bdb(&name, BASE64_STANDARD.decode(&data).unwrap())
+                    .await;
+            })
+        })
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_get_incoming_friends() -> String {
     #[cfg(feature = "debug")]
     println!("get_incoming_friends()");
-    RUNTIME.block_on(async {
-        let fr = CRYSTAL
-            .lock()
-            .await
-            .get_incoming_friends()
-            .await
-            .unwrap_or_default();
-        let mut s = format!("{}", fr.len());
-        for pid in fr {
-            s.push_str(&format!(":{pid}"));
-        }
-        s
-    })
+    client()
+        .call(move |crystal| {
+            Box::pin(async move {
+                encode_friends(
+                    crystal
+                        .get_incoming_friends()
+                        .await
+                        .unwrap_or_default()
+                        .into_iter(),
+                )
+            })
+        })
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_get_outgoing_friends() -> String {
     #[cfg(feature = "debug")]
     println!("get_outgoing_friends()");
-    RUNTIME.block_on(async {
-        let fr = CRYSTAL
-            .lock()
-            .await
-            .get_outgoing_friends()
-            .await
-            .unwrap_or_default();
-        let mut s = format!("{}", fr.len());
-        for pid in fr {
-            s.push_str(&format!(":{pid}"));
-        }
-        s
-    })
+    client()
+        .call(move |crystal| {
+            Box::pin(async move {
+                encode_friends(
+                    crystal
+                        .get_outgoing_friends()
+                        .await
+                        .unwrap_or_default()
+                        .into_iter(),
+                )
+            })
+        })
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_get_friends() -> String {
     #[cfg(feature = "debug")]
     println!("get_friends()");
-    RUNTIME.block_on(async {
-        let fr = CRYSTAL.lock().await.get_friends().await.unwrap_or_default();
-        let mut s = format!("{}", fr.len());
-        for pid in fr {
-            s.push_str(&format!(":{pid}"));
-        }
-        s
-    })
+    client()
+        .call(move |crystal| {
+            Box::pin(async move {
+                encode_friends(crystal.get_friends().await.unwrap_or_default().into_iter())
+            })
+        })
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_send_outgoing_friend(pid: f64) {
     #[cfg(feature = "debug")]
     println!("send_outgoing_friend({pid:?})");
-    RUNTIME.block_on(async {
-        let _ = CRYSTAL.lock().await.send_outgoing_friend(pid as u64).await;
-    })
+    client()
+        .call(move |crystal| {
+            Box::pin(async move {
+                let _ = crystal.send_outgoing_friend(pid as u64).await;
+            })
+        })
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_remove_outgoing_friend(pid: f64) {
     #[cfg(feature = "debug")]
     println!("remove_outgoing_friend({pid:?})");
-    RUNTIME.block_on(async {
-        let _ = CRYSTAL
-            .lock()
-            .await
-            .remove_outgoing_friend(pid as u64)
-            .await;
-    })
+    client()
+        .call(move |crystal| {
+            Box::pin(async move {
+                let _ = crystal.remove_outgoing_friend(pid as u64).await;
+            })
+        })
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_deny_incoming_friend(pid: f64) {
     #[cfg(feature = "debug")]
     println!("deny_incoming_friend({pid:?})");
-    RUNTIME.block_on(async {
-        let _ = CRYSTAL.lock().await.deny_incoming_friend(pid as u64).await;
-    })
+    client()
+        .call(move |crystal| {
+            Box::pin(async move {
+                let _ = crystal.deny_incoming_friend(pid as u64).await;
+            })
+        })
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_accept_incoming_friend(pid: f64) {
     #[cfg(feature = "debug")]
     println!("accept_incoming_friend({pid:?})");
-    RUNTIME.block_on(async {
-        let _ = CRYSTAL
-            .lock()
-            .await
-            .accept_incoming_friend(pid as u64)
-            .await;
-    })
+    client()
+        .call(move |crystal| {
+            Box::pin(async move {
+                let _ = crystal.accept_incoming_friend(pid as u64).await;
+            })
+        })
+        .unwrap_or_default()
 }
 
 #[gm_func]
 pub fn __crystal_remove_friend(pid: f64) {
     #[cfg(feature = "debug")]
     println!("remove_friend({pid:?})");
-    RUNTIME.block_on(async {
-        let _ = CRYSTAL.lock().await.remove_friend(pid as u64).await;
+    client()
+        .call(move |crystal| {
+            Box::pin(async move {
+                let _ = crystal.remove_friend(pid as u64).await;
+            })
+        })
+        .unwrap_or_default()
+}
+
+/// Joins encoded items, into an array in JSON output mode.
+fn encode_list(items: Vec<String>) -> String {
+    if json::output() {
+        format!("[{}]", items.join(","))
+    } else {
+        items.join(";")
+    }
+}
+
+/// What's returned in place of a missing player, sync, achievement, etc.
+fn encode_none() -> String {
+    String::from(if json::output() { "null" } else { "!" })
+}
+
+fn variables_json(variables: &HashMap<String, Value>) -> Json {
+    Json::Object(
+        members(variables)
+            .into_iter()
+            .map(|(name, value)| (name.clone(), json::to_json(value)))
+            .collect(),
+    )
+}
+
+fn sync_json(sync: &Option<types::Sync>) -> Json {
+    let Some(sync) = sync else {
+        return Json::Null;
+    };
+    json!({
+        "kind": sync.kind,
+        "sync_type": sync.sync_type as u64,
+        "event": sync.event as u64,
+        "is_ending": sync.is_ending,
+        "variables": variables_json(&sync.variables),
     })
 }
 
 fn encode_player(pid: u64, player: &Player) -> String {
+    if json::output() {
+        return json!({
+            "id": pid,
+            "name": player.name,
+            "rm": player.room,
+            "syncs": player.syncs.iter().map(sync_json).collect::<Vec<_>>(),
+            "variables": variables_json(&player.variables),
+        })
+        .to_string();
+    }
     let mut s = format!(
         "{pid}:{}:{}:{}:{}",
         BASE64_STANDARD.encode(&player.name),
@@ -1073,7 +1253,7 @@ fn encode_player(pid: u64, player: &Player) -> String {
             BASE64_STANDARD.encode(encode_sync(index, sync))
         ));
     }
-    for (name, value) in &player.variables {
+    for (name, value) in members(&player.variables) {
         s.push_str(&format!(
             ":{}:{}",
             BASE64_STANDARD.encode(name),
@@ -1093,7 +1273,7 @@ fn encode_sync(index: usize, sync: &Option<types::Sync>) -> String {
             sync.is_ending as u8,
             sync.variables.len()
         );
-        for (name, value) in &sync.variables {
+        for (name, value) in members(&sync.variables) {
             s.push_str(&format!(
                 ":{}:{}",
                 BASE64_STANDARD.encode(name),
@@ -1107,39 +1287,64 @@ fn encode_sync(index: usize, sync: &Option<types::Sync>) -> String {
 }
 
 fn encode_achievement(achievement: &Option<Achievement>) -> String {
-    if let Some(achi) = achievement {
-        format!(
-            "{}:{}:{}",
-            BASE64_STANDARD.encode(&achi.name),
-            BASE64_STANDARD.encode(&achi.description),
-            if let Some(unlocked) = achi.unlocked {
-                unlocked.to_string()
-            } else {
-                String::from("!")
-            }
-        )
-    } else {
-        String::from("!")
+    let Some(achi) = achievement else {
+        return encode_none();
+    };
+    if json::output() {
+        return json!({
+            "name": achi.name,
+            "description": achi.description,
+            "unlocked": achi.unlocked,
+        })
+        .to_string();
     }
+    format!(
+        "{}:{}:{}",
+        BASE64_STANDARD.encode(&achi.name),
+        BASE64_STANDARD.encode(&achi.description),
+        if let Some(unlocked) = achi.unlocked {
+            unlocked.to_string()
+        } else {
+            String::from("!")
+        }
+    )
 }
 
 fn encode_highscore(highscore: &Option<Highscore>) -> String {
-    if let Some(hscr) = highscore {
-        let mut s = format!(
-            "{}:{}",
-            BASE64_STANDARD.encode(&hscr.name),
-            hscr.scores.len()
-        );
-        for (pid, score) in hscr.scores.iter() {
-            s.push_str(&format!(":{}:{score}", **pid));
-        }
-        s
-    } else {
-        String::from("!")
+    let Some(hscr) = highscore else {
+        return encode_none();
+    };
+    if json::output() {
+        let scores = hscr
+            .scores
+            .iter()
+            .map(|(pid, score)| ((**pid).to_string(), json!(score)))
+            .collect::<serde_json::Map<_, _>>();
+        return json!({ "name": hscr.name, "scores": scores }).to_string();
     }
+    let mut s = format!(
+        "{}:{}",
+        BASE64_STANDARD.encode(&hscr.name),
+        hscr.scores.len()
+    );
+    for (pid, score) in hscr.scores.iter() {
+        s.push_str(&format!(":{}:{score}", **pid));
+    }
+    s
 }
 
 fn encode_synciter(siter: &SyncIter) -> String {
+    if json::output() {
+        return json!({
+            "id": siter.player_id,
+            "name": siter.player_name,
+            "sync_slot": siter.slot,
+            "event": siter.event as u64,
+            "kind": siter.kind,
+            "variables": variables_json(&siter.variables),
+        })
+        .to_string();
+    }
     let mut s = format!(
         "{}:{}:{}:{}:{}:{}",
         siter.player_id,
@@ -1149,7 +1354,7 @@ fn encode_synciter(siter: &SyncIter) -> String {
         siter.kind,
         siter.variables.len()
     );
-    for (name, value) in &siter.variables {
+    for (name, value) in members(&siter.variables) {
         s.push_str(&format!(
             ":{}:{}",
             BASE64_STANDARD.encode(name),
@@ -1159,76 +1364,35 @@ fn encode_synciter(siter: &SyncIter) -> String {
     s
 }
 
-fn encode_administrator(admin: &Administrator) -> String {
-    format!("{}:{}:{}", admin.can_ban, admin.can_unban, admin.can_kick)
+fn encode_friends(friends: impl ExactSizeIterator<Item = u64>) -> String {
+    if json::output() {
+        return Json::from(friends.collect::<Vec<_>>()).to_string();
+    }
+    let mut s = format!("{}", friends.len());
+    for pid in friends {
+        s.push_str(&format!(":{pid}"));
+    }
+    s
 }
 
-fn encode_vari(vari: &Value) -> String {
-    match vari {
-        Value::Null => String::from("!"),
-        Value::Int(val) => format!("0:{val}"),
-        Value::Float(val) => format!("1:{val}"),
-        Value::Bool(val) => format!("2:{}", *val as u8),
-        Value::String(val) => format!("3:{}", BASE64_STANDARD.encode(val)),
-        Value::Buffer(val) => format!("4:{}", BASE64_STANDARD.encode(val)),
-        Value::Array(val) => {
-            let mut s = format!("5:{}", val.len());
-            for val in val {
-                s.push_str(&format!(":{}", BASE64_STANDARD.encode(encode_vari(val))));
-            }
-            s
-        }
-        Value::Struct(val) => {
-            let mut s = format!("6:{}", val.len());
-            for (name, val) in val {
-                s.push_str(&format!(
-                    ":{}:{}",
-                    BASE64_STANDARD.encode(name),
-                    BASE64_STANDARD.encode(encode_vari(val))
-                ));
-            }
-            s
-        }
+fn encode_administrator(admin: &Administrator) -> String {
+    if json::output() {
+        return json!({
+            "can_ban": admin.can_ban,
+            "can_unban": admin.can_unban,
+            "can_kick": admin.can_kick,
+        })
+        .to_string();
     }
+    format!("{}:{}:{}", admin.can_ban, admin.can_unban, admin.can_kick)
 }
 
-fn decode_vari(s: &str) -> Value {
-    let mut s = s.split(":");
-    match s.next() {
-        Some("!") => Value::Null,
-        Some("0") => Value::Int(s.next().unwrap().parse::<i64>().unwrap()),
-        Some("1") => Value::Float(s.next().unwrap().parse::<f64>().unwrap()),
-        Some("2") => Value::Bool(s.next().unwrap().parse::<i64>().unwrap() != 0),
-        Some("3") => Value::String(
-            String::from_utf8_lossy(&BASE64_STANDARD.decode(s.next().unwrap()).unwrap())
-                .to_string(),
-        ),
-        Some("4") => Value::Buffer(BASE64_STANDARD.decode(s.next().unwrap()).unwrap()),
-        Some("5") => Value::Array({
-            let mut v = Vec::new();
-            for _ in 0..s.next().unwrap().parse::<usize>().unwrap() {
-                v.push(decode_vari(&String::from_utf8_lossy(
-                    &BASE64_STANDARD.decode(s.next().unwrap()).unwrap(),
-                )));
-            }
-            v
-        }),
-        Some("6") => Value::Struct({
-            let mut v = HashMap::new();
-            for _ in 0..s.next().unwrap().parse::<usize>().unwrap() {
-                let name =
-                    String::from_utf8_lossy(&BASE64_STANDARD.decode(s.next().unwrap()).unwrap())
-                        .to_string();
-                v.insert(
-                    name,
-                    decode_vari(&String::from_utf8_lossy(
-                        &BASE64_STANDARD.decode(s.next().unwrap()).unwrap(),
-                    )),
-                );
-            }
-            v
-        }),
-        Some(_) => Value::Null,
-        None => Value::Null,
+fn decode_player_request(target: f64) -> PlayerRequest {
+    match target {
+        -1.0 => PlayerRequest::AllGame,
+        -2.0 => PlayerRequest::CurrentRoom,
+        -3.0 => PlayerRequest::CurrentSession,
+        -4.0 => PlayerRequest::Server,
+        _ => PlayerRequest::ID(target as u64),
     }
 }
@@ -0,0 +1,112 @@
+//! Tearing the clients down again, so the extension can be initialized anew in the same process.
+//!
+//! The runtime and the client tasks are created by the first export that needs them and torn
+//! down by `__crystal_shutdown`, which GameMaker calls as the extension's final function. The
+//! next export needing them creates them again, with the settings configured at that point.
+
+use std::{
+    sync::{Arc, atomic::Ordering},
+    time::Instant,
+};
+
+use futures_util::future::join_all;
+use gm_utils::gm_func;
+use parking_lot::RwLock;
+
+use crate::{RUNTIME, config, instance, ticket};
+
+/// A value created on first use, which can be taken out to be created again on the next one.
+pub(crate) struct Restartable<T> {
+    value: RwLock<Option<Arc<T>>>,
+    init: fn() -> T,
+}
+
+impl<T> Restartable<T> {
+    pub const fn new(init: fn() -> T) -> Self {
+        Self {
+            value: RwLock::new(None),
+            init,
+        }
+    }
+
+    /// The current value, created if there's none.
+    pub fn get(&self) -> Arc<T> {
+        if let Some(value) = &*self.value.read() {
+            return value.clone();
+        }
+        self.value
+            .write()
+            .get_or_insert_with(|| Arc::new((self.init)()))
+            .clone()
+    }
+
+    /// Takes the current value out, if it was created.
+    pub fn take(&self) -> Option<Arc<T>> {
+        self.value.write().take()
+    }
+}
+
+/// Disconnects every instance, waits up to `shutdown_timeout` for the calls still queued and
+/// the tickets still running, then stops the runtime.
+///
+/// Instances and their queued notifications stay, so handles remain valid. Calling
+/// `__crystal_init` afterwards starts over, and calling this when nothing was started does
+/// nothing.
+#[gm_func]
+pub fn __crystal_shutdown() {
+    #[cfg(feature = "debug")]
+    println!("shutdown()");
+    let instances = instance::all();
+    for instance in &instances {
+        instance.reconnect.stop();
+    }
+    let clients = instances
+        .iter()
+        .filter_map(|instance| instance.client.take())
+        .collect::<Vec<_>>();
+    let Some(runtime) = RUNTIME.take() else {
+        return;
+    };
+    config::stopping();
+    let deadline = Instant::now() + config::shutdown_timeout();
+    runtime.block_on(async {
+        for instance in &instances {
+            *instance.has_init.lock().await = false;
+            instance.reached.lock().clear();
+            instance.has_version.store(false, Ordering::Relaxed);
+        }
+        let drained = async {
+            // Calls run in order, so once the disconnect ran every earlier call did too.
+            let disconnected = clients.iter().map(|client| {
+                client.run(|crystal| Box::pin(async move { crystal.disconnect().await }))
+            });
+            join_all(disconnected).await;
+            ticket::drained().await;
+        };
+        let _ = tokio::time::timeout_at(deadline.into(), drained).await;
+    });
+    // Dropping the last handle of a client ends its task, taking the connection with it.
+    drop(clients);
+    if let Ok(runtime) = Arc::try_unwrap(runtime) {
+        runtime.shutdown_timeout(deadline.saturating_duration_since(Instant::now()));
+    }
+    config::stopped();
+}
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    #[test]
+    fn values_are_created_again_after_being_taken() {
+        static CREATED: AtomicUsize = AtomicUsize::new(0);
+        static VALUE: Restartable<usize> =
+            Restartable::new(|| CREATED.fetch_add(1, Ordering::Relaxed));
+        assert!(VALUE.take().is_none());
+        assert_eq!(*VALUE.get(), 0);
+        assert_eq!(*VALUE.get(), 0);
+        assert_eq!(VALUE.take().as_deref(), Some(&0));
+        assert_eq!(*VALUE.get(), 1);
+    }
+}
@@ -0,0 +1,1385 @@
+//! Everything the client has to tell the game.
+//!
+//! Notifications are delivered as Social async events once Gamemaker registered its callbacks
+//! and the game opted in through `__crystal_set_async_events`. Otherwise, or if creating the
+//! event fails, they're queued and polled with `__crystal_get_notification` as before.
+//!
+//! Every notification gets a sequence number, increasing by one in delivery order, so the
+//! game can tell when it missed some. Polled notifications are prefixed with it (`{seq};`).
+//!
+//! Each client instance has its own queue and sequence numbers, see [`instance`].
+//!
+//! An async event's `async_load` always holds `type` (`"crystal"`), `handle` (the instance),
+//! `seq` and `kind`, the first segment of the polled notification after the sequence number,
+//! followed by the fields listed in [`Notification::fields`]. Unlike the polled text, strings
+//! aren't base64-encoded, except for binary data. Values keep the polled encoding, with `!!`
+//! for removed or missing ones.
+//!
+//! `__crystal_drain_notifications_binary` writes the same fields as binary records instead,
+//! see [`binary`].
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, VecDeque},
+    os::raw::c_char,
+    sync::Arc,
+    time::Duration,
+};
+
+use base64::{Engine, prelude::BASE64_STANDARD};
+use crystal_server::types::{AdminAction, DataUpdate, OptionalValue, Value};
+use gm_utils::{
+    async_event::{self, AsyncEvent, DsMap},
+    buffer::GmBuffer,
+    gm_func,
+};
+
+use parking_lot::{Mutex, RawMutex, lock_api::ArcMutexGuard};
+
+use crate::{binary, connection::ConnectError, encode_vari, instance};
+
+/// The limit and policy of queues created from now on, see `__crystal_configure`.
+static DEFAULTS: Mutex<(usize, OverflowPolicy)> = Mutex::new((0, OverflowPolicy::DropOldest));
+
+/// Notifications waiting to be polled, in the order they were pushed.
+///
+/// Sequence numbers are handed out under the same lock that orders the entries, so they're
+/// strictly increasing in delivery order, whether polled or sent as async events.
+///
+/// With a `limit`, the queue never holds more than that many entries and the `policy` decides
+/// which one goes when another arrives. Dropped entries keep their sequence number, so the game
+/// sees them as a gap.
+pub(crate) struct Queue {
+    /// The instance the queue belongs to, sent along with async events.
+    handle: u64,
+    next_seq: u64,
+    async_events: bool,
+    entries: VecDeque<(u64, Notification)>,
+    /// `0` means unbounded.
+    limit: usize,
+    policy: OverflowPolicy,
+    dropped: BTreeMap<&'static str, u64>,
+    coalescing: bool,
+    /// Sequence number of the queued value each variable can still be coalesced into.
+    pending: HashMap<PendingKey, u64>,
+    /// Kinds the game wants, `None` for all of them.
+    subscriptions: Option<BTreeSet<&'static str>>,
+}
+
+/// Player ID, sync slot (`None` for player variables) and variable name.
+type PendingKey = (u64, Option<usize>, String);
+
+/// The variable a notification sets, if it can be coalesced.
+fn pending_key(notification: &Notification) -> Option<PendingKey> {
+    match notification {
+        Notification::Data(DataUpdate::UpdateVariable(pid, name, OptionalValue::Some(_))) => {
+            Some((*pid, None, name.clone()))
+        }
+        Notification::Data(DataUpdate::UpdateSyncVariable(
+            pid,
+            slot,
+            name,
+            OptionalValue::Some(_),
+        )) => Some((*pid, Some(*slot), name.clone())),
+        _ => None,
+    }
+}
+
+/// What happens when a notification arrives while the queue is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OverflowPolicy {
+    DropOldest = 0,
+    DropNewest = 1,
+    /// Drops the oldest queued notification the new one supersedes (see [`Notification::key`]),
+    /// or the oldest one if there's none.
+    Coalesce = 2,
+}
+
+impl OverflowPolicy {
+    fn from_f64(policy: f64) -> Option<Self> {
+        match policy as i64 {
+            0 => Some(Self::DropOldest),
+            1 => Some(Self::DropNewest),
+            2 => Some(Self::Coalesce),
+            _ => None,
+        }
+    }
+
+    fn from_name(policy: &str) -> Option<Self> {
+        match policy {
+            "drop_oldest" => Some(Self::DropOldest),
+            "drop_newest" => Some(Self::DropNewest),
+            "coalesce" => Some(Self::Coalesce),
+            policy => Self::from_f64(policy.parse().ok()?),
+        }
+    }
+}
+
+impl Queue {
+    pub fn new(handle: u64) -> Self {
+        let (limit, policy) = *DEFAULTS.lock();
+        Self {
+            handle,
+            next_seq: 1,
+            async_events: false,
+            entries: VecDeque::new(),
+            limit,
+            policy,
+            dropped: BTreeMap::new(),
+            coalescing: false,
+            pending: HashMap::new(),
+            subscriptions: None,
+        }
+    }
+}
+
+impl Queue {
+    /// Tickets answer calls the game made, so they can't be unsubscribed from.
+    fn is_subscribed(&self, kind: &str) -> bool {
+        kind == "ticket"
+            || self
+                .subscriptions
+                .as_ref()
+                .is_none_or(|subscriptions| subscriptions.contains(kind))
+    }
+
+    /// Writes `notification` over the queued value of the same variable, returning it back
+    /// if it has to be queued instead.
+    ///
+    /// A value is only replaced while nothing that affects its variable was queued after it,
+    /// so removals, `update_sync_removal` and logouts keep their order relative to values.
+    fn coalesce(&mut self, notification: Notification) -> Option<Notification> {
+        if !self.coalescing || self.async_events {
+            return Some(notification);
+        }
+        match &notification {
+            Notification::Data(DataUpdate::UpdateVariable(pid, name, OptionalValue::None)) => {
+                self.pending.remove(&(*pid, None, name.clone()));
+            }
+            Notification::Data(DataUpdate::UpdateSyncVariable(
+                pid,
+                slot,
+                name,
+                OptionalValue::None,
+            )) => {
+                self.pending.remove(&(*pid, Some(*slot), name.clone()));
+            }
+            Notification::Data(DataUpdate::UpdateSyncRemoval(pid, slot)) => {
+                self.pending
+                    .retain(|(key_pid, key_slot, _), _| (key_pid, *key_slot) != (pid, Some(*slot)));
+            }
+            Notification::Data(DataUpdate::PlayerLoggedOut(pid)) => {
+                self.pending.retain(|(key_pid, _, _), _| key_pid != pid);
+            }
+            Notification::Data(
+                DataUpdate::Disconnected() | DataUpdate::Reconnecting() | DataUpdate::LoginOk(..),
+            ) => {
+                self.pending.clear();
+            }
+            _ => (),
+        }
+        if let Some(key) = pending_key(&notification)
+            && let Some(&seq) = self.pending.get(&key)
+            && let Ok(index) = self.entries.binary_search_by_key(&seq, |(seq, _)| *seq)
+        {
+            self.entries[index].1 = notification;
+            return None;
+        }
+        Some(notification)
+    }
+
+    fn pop_entry(&mut self) -> Option<(u64, Notification)> {
+        let (seq, notification) = self.entries.pop_front()?;
+        if let Some(key) = pending_key(&notification)
+            && self.pending.get(&key) == Some(&seq)
+        {
+            self.pending.remove(&key);
+        }
+        Some((seq, notification))
+    }
+
+    /// Pops the oldest notification as polled text.
+    fn pop(&mut self) -> Option<String> {
+        let (seq, notification) = self.pop_entry()?;
+        Some(format!("{seq};{}", notification.to_text()))
+    }
+
+    /// Returns whether `notification` was queued, rather than sent as an async event or
+    /// dropped.
+    fn deliver(&mut self, seq: u64, notification: Notification) -> bool {
+        if self.async_events
+            && let Some(map) = notification.to_ds_map(self.handle, seq)
+        {
+            map.dispatch(AsyncEvent::Social);
+            return false;
+        }
+        if self.limit != 0 && self.entries.len() >= self.limit {
+            let index = match self.policy {
+                OverflowPolicy::DropOldest => 0,
+                OverflowPolicy::DropNewest => {
+                    self.count_dropped(&notification);
+                    return false;
+                }
+                OverflowPolicy::Coalesce => notification
+                    .key()
+                    .and_then(|key| {
+                        self.entries
+                            .iter()
+                            .position(|(_, queued)| queued.key().as_ref() == Some(&key))
+                    })
+                    .unwrap_or(0),
+            };
+            if let Some((_, dropped)) = self.entries.remove(index) {
+                self.count_dropped(&dropped);
+            }
+        }
+        self.entries.push_back((seq, notification));
+        true
+    }
+
+    /// Drops entries until the queue fits its limit again, used after lowering it.
+    fn truncate(&mut self) {
+        while self.limit != 0 && self.entries.len() > self.limit {
+            let dropped = match self.policy {
+                OverflowPolicy::DropNewest => self.entries.pop_back(),
+                OverflowPolicy::DropOldest | OverflowPolicy::Coalesce => self.entries.pop_front(),
+            };
+            if let Some((_, dropped)) = dropped {
+                self.count_dropped(&dropped);
+            }
+        }
+    }
+
+    fn count_dropped(&mut self, notification: &Notification) {
+        *self.dropped.entry(notification.kind()).or_default() += 1;
+    }
+}
+
+/// Identifies the state a notification updates, a newer notification with the same key
+/// makes the older one obsolete.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) enum Key<'a> {
+    Variable(u64, &'a str),
+    SyncVariable(u64, usize, &'a str),
+    GameIni(Option<&'a str>, &'a str, &'a str),
+    PlayerIni(Option<&'a str>, &'a str, &'a str),
+    GameVersion,
+    Administrator(u64),
+}
+
+/// Every [`Notification::kind`].
+const KINDS: [&str; 29] = [
+    "admin_action",
+    "banned",
+    "friend_status",
+    "disconnected",
+    "fetch_bdb",
+    "kicked",
+    "login",
+    "login_ok",
+    "login_ban",
+    "p2p",
+    "register",
+    "player_logged_in",
+    "player_logged_out",
+    "reconnecting",
+    "server_message",
+    "update_variable",
+    "update_sync_variable",
+    "update_sync_removal",
+    "update_gameini",
+    "update_playerini",
+    "update_gameversion",
+    "update_administrator",
+    "server_notification",
+    "login_token",
+    "player_variable_request",
+    "sync_variable_request",
+    "ticket",
+    "connect_result",
+    "reconnect_failed",
+];
+
+#[derive(Debug, Clone)]
+pub(crate) enum Notification {
+    Data(DataUpdate),
+    PlayerVariableRequest {
+        request: f64,
+        pid: u64,
+        name: String,
+        value: OptionalValue,
+    },
+    SyncVariableRequest {
+        request: f64,
+        pid: u64,
+        name: String,
+        value: OptionalValue,
+    },
+    Ticket {
+        id: u64,
+        result: Result<String, String>,
+    },
+    /// How a `__crystal_connect` went, see [`crate::connection`].
+    ConnectResult(Result<(), ConnectError>),
+    /// Attempt `attempt` to reconnect is made in `delay`, see [`crate::reconnect`]. Without a
+    /// reconnect policy, the server moving the client to another host is reported as an
+    /// immediate first attempt.
+    Reconnecting {
+        attempt: u32,
+        delay: Duration,
+    },
+    /// Reconnecting gave up after `attempts` attempts.
+    ReconnectFailed {
+        attempts: u32,
+    },
+}
+
+/// A single field of a notification, see [`Notification::fields`].
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Field {
+    Real(f64),
+    Text(String),
+    Bytes(Vec<u8>),
+    /// `None` for removed or missing values.
+    Value(OptionalValue),
+}
+
+fn encode_optional_vari(value: &OptionalValue, none: &str) -> String {
+    if let OptionalValue::Some(value) = value {
+        encode_vari(value)
+    } else {
+        String::from(none)
+    }
+}
+
+fn removed(value: &OptionalValue) -> Field {
+    Field::Real(if let OptionalValue::Some(_) = value {
+        0.0
+    } else {
+        1.0
+    })
+}
+
+impl Notification {
+    /// The first segment of the polled text and the `kind` of the async event.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Notification::Data(data) => match data {
+                DataUpdate::AdminAction(_) => "admin_action",
+                DataUpdate::Banned(..) => "banned",
+                DataUpdate::ChangeFriendStatus(_) => "friend_status",
+                DataUpdate::Disconnected() => "disconnected",
+                DataUpdate::FetchBdb(..) => "fetch_bdb",
+                DataUpdate::Kicked(_) => "kicked",
+                DataUpdate::Login(_) => "login",
+                DataUpdate::LoginOk(..) => "login_ok",
+                DataUpdate::LoginBan(..) => "login_ban",
+                DataUpdate::P2P(..) => "p2p",
+                DataUpdate::Registration(_) => "register",
+                DataUpdate::PlayerLoggedIn(..) => "player_logged_in",
+                DataUpdate::PlayerLoggedOut(_) => "player_logged_out",
+                DataUpdate::Reconnecting() => "reconnecting",
+                DataUpdate::ServerMessage(_) => "server_message",
+                DataUpdate::UpdateVariable(..) => "update_variable",
+                DataUpdate::UpdateSyncVariable(..) => "update_sync_variable",
+                DataUpdate::UpdateSyncRemoval(..) => "update_sync_removal",
+                DataUpdate::UpdateGameIni(..) => "update_gameini",
+                DataUpdate::UpdatePlayerIni(..) => "update_playerini",
+                DataUpdate::UpdateGameVersion(_) => "update_gameversion",
+                DataUpdate::UpdateAdministrator(..) => "update_administrator",
+                DataUpdate::ServerNotification(_) => "server_notification",
+                DataUpdate::LoginToken(_) => "login_token",
+            },
+            Notification::PlayerVariableRequest { .. } => "player_variable_request",
+            Notification::SyncVariableRequest { .. } => "sync_variable_request",
+            Notification::Ticket { .. } => "ticket",
+            Notification::ConnectResult(_) => "connect_result",
+            Notification::Reconnecting { .. } => "reconnecting",
+            Notification::ReconnectFailed { .. } => "reconnect_failed",
+        }
+    }
+
+    /// See [`Key`], notifications which don't just update some state have none.
+    pub fn key(&self) -> Option<Key<'_>> {
+        match self {
+            Notification::Data(data) => match data {
+                DataUpdate::UpdateVariable(pid, name, _) => Some(Key::Variable(*pid, name)),
+                DataUpdate::UpdateSyncVariable(pid, slot, name, _) => {
+                    Some(Key::SyncVariable(*pid, *slot, name))
+                }
+                DataUpdate::UpdateGameIni(file, section, key, _) => {
+                    Some(Key::GameIni(file.as_deref(), section, key))
+                }
+                DataUpdate::UpdatePlayerIni(file, section, key, _) => {
+                    Some(Key::PlayerIni(file.as_deref(), section, key))
+                }
+                DataUpdate::UpdateGameVersion(_) => Some(Key::GameVersion),
+                DataUpdate::UpdateAdministrator(pid, _) => Some(Key::Administrator(*pid)),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// The `;`-separated text returned by `__crystal_get_notification`.
+    pub fn to_text(&self) -> String {
+        match self {
+            Notification::Data(data) => match data {
+                DataUpdate::AdminAction(aa) => match aa {
+                    AdminAction::Unban => String::from("admin_action;0"),
+                    AdminAction::Ban(reason, unban_time) => {
+                        format!(
+                            "admin_action;1;{};{unban_time}",
+                            BASE64_STANDARD.encode(reason)
+                        )
+                    }
+                    AdminAction::Kick(reason) => {
+                        format!("admin_action;2;{}", BASE64_STANDARD.encode(reason))
+                    }
+                },
+                DataUpdate::Banned(reason, unban_time) => {
+                    format!(
+                        "banned;{};{}",
+                        BASE64_STANDARD.encode(reason),
+                        unban_time.timestamp()
+                    )
+                }
+                DataUpdate::ChangeFriendStatus(status) => format!("friend_status;{status}"),
+                DataUpdate::Disconnected() => String::from("disconnected"),
+                DataUpdate::FetchBdb(name, value) => {
+                    if let Some(value) = value {
+                        format!(
+                            "fetch_bdb;1;{};{}",
+                            BASE64_STANDARD.encode(name),
+                            BASE64_STANDARD.encode(value)
+                        )
+                    } else {
+                        format!("fetch_bdb;0;{}", BASE64_STANDARD.encode(name))
+                    }
+                }
+                DataUpdate::Kicked(reason) => {
+                    format!("kicked;{}", BASE64_STANDARD.encode(reason))
+                }
+                DataUpdate::Login(code) => format!("login;{}", *code as u64),
+                DataUpdate::LoginOk(pid, name) => {
+                    format!("login_ok;{pid};{}", BASE64_STANDARD.encode(name))
+                }
+                DataUpdate::LoginBan(code, reason, unban_time) => {
+                    format!(
+                        "login_ban;{};{};{unban_time}",
+                        *code as u64,
+                        BASE64_STANDARD.encode(reason)
+                    )
+                }
+                DataUpdate::P2P(sender, mid, payload) => {
+                    format!(
+                        "p2p;{};{mid};{}",
+                        if let Some(sender) = sender {
+                            sender.to_string()
+                        } else {
+                            String::from("!")
+                        },
+                        encode_vari(&Value::Array(payload.clone()))
+                    )
+                }
+                DataUpdate::Registration(code) => format!("register;{}", *code as u64),
+                DataUpdate::PlayerLoggedIn(pid, name, room) => {
+                    format!(
+                        "player_logged_in;{pid};{};{}",
+                        BASE64_STANDARD.encode(name),
+                        BASE64_STANDARD.encode(room)
+                    )
+                }
+                DataUpdate::PlayerLoggedOut(pid) => format!("player_logged_out;{pid}"),
+                // Only pushed without a reconnect policy, see `reconnect::superseded`.
+                DataUpdate::Reconnecting() => String::from("reconnecting;1;0"),
+                DataUpdate::ServerMessage(message) => {
+                    format!("server_message;{}", BASE64_STANDARD.encode(message))
+                }
+                DataUpdate::UpdateVariable(pid, name, value) => {
+                    format!(
+                        "update_variable;{pid};{};{}",
+                        BASE64_STANDARD.encode(name),
+                        encode_optional_vari(value, "!!")
+                    )
+                }
+                DataUpdate::UpdateSyncVariable(pid, slot, name, value) => {
+                    format!(
+                        "update_sync_variable;{pid};{slot};{};{}",
+                        BASE64_STANDARD.encode(name),
+                        encode_optional_vari(value, "!!")
+                    )
+                }
+                DataUpdate::UpdateSyncRemoval(pid, slot) => {
+                    format!("update_sync_removal;{pid};{slot}")
+                }
+                DataUpdate::UpdateGameIni(file, section, key, value) => {
+                    format!(
+                        "update_gameini;{};{};{};{}",
+                        if let Some(file) = file {
+                            BASE64_STANDARD.encode(file)
+                        } else {
+                            String::from("!!")
+                        },
+                        BASE64_STANDARD.encode(section),
+                        BASE64_STANDARD.encode(key),
+                        encode_optional_vari(value, "!!")
+                    )
+                }
+                DataUpdate::UpdatePlayerIni(file, section, key, value) => {
+                    format!(
+                        "update_playerini;{};{};{};{}",
+                        if let Some(file) = file {
+                            BASE64_STANDARD.encode(file)
+                        } else {
+                            String::from("!!")
+                        },
+                        BASE64_STANDARD.encode(section),
+                        BASE64_STANDARD.encode(key),
+                        encode_optional_vari(value, "!!")
+                    )
+                }
+                DataUpdate::UpdateGameVersion(ver) => format!("update_gameversion;{ver}"),
+                DataUpdate::UpdateAdministrator(pid, admin) => {
+                    format!(
+                        "update_administrator;{pid};{}",
+                        if let Some(admin) = admin {
+                            format!("{}:{}:{}", admin.can_ban, admin.can_unban, admin.can_kick)
+                        } else {
+                            String::from("!")
+                        }
+                    )
+                }
+                DataUpdate::ServerNotification(notif) => {
+                    format!("server_notification;{}", BASE64_STANDARD.encode(notif))
+                }
+                DataUpdate::LoginToken(token) => {
+                    format!("login_token;{}", BASE64_STANDARD.encode(token))
+                }
+            },
+            Notification::PlayerVariableRequest {
+                request,
+                pid,
+                name,
+                value,
+            } => format!(
+                "player_variable_request;{request};{pid};{name};{}",
+                encode_optional_vari(value, "!")
+            ),
+            Notification::SyncVariableRequest {
+                request,
+                pid,
+                name,
+                value,
+            } => format!(
+                "sync_variable_request;{request};{pid};{name};{}",
+                encode_optional_vari(value, "!")
+            ),
+            Notification::Ticket { id, result } => match result {
+                Ok(result) => format!("ticket;{id};1;{}", BASE64_STANDARD.encode(result)),
+                Err(err) => format!("ticket;{id};0;{}", BASE64_STANDARD.encode(err)),
+            },
+            Notification::ConnectResult(Ok(())) => String::from("connect_result;1"),
+            Notification::ConnectResult(Err(err)) => format!(
+                "connect_result;0;{};{}",
+                err.category.name(),
+                BASE64_STANDARD.encode(&err.message)
+            ),
+            Notification::Reconnecting { attempt, delay } => {
+                format!("reconnecting;{attempt};{}", delay.as_millis())
+            }
+            Notification::ReconnectFailed { attempts } => format!("reconnect_failed;{attempts}"),
+        }
+    }
+
+    /// The fields of the async event and the binary record, without `type`, `seq` and `kind`.
+    ///
+    /// Removed variables are also flagged by `removed`. Optional fields (`file`, `value` of
+    /// `fetch_bdb` and the administrator permissions) are left out when missing.
+    pub fn fields(&self) -> Vec<(&'static str, Field)> {
+        match self {
+            Notification::Data(data) => match data {
+                DataUpdate::AdminAction(aa) => match aa {
+                    AdminAction::Unban => vec![("action", Field::Real(0.0))],
+                    AdminAction::Ban(reason, unban_time) => vec![
+                        ("action", Field::Real(1.0)),
+                        ("reason", Field::Text(reason.clone())),
+                        ("unban_time", Field::Real(*unban_time as f64)),
+                    ],
+                    AdminAction::Kick(reason) => vec![
+                        ("action", Field::Real(2.0)),
+                        ("reason", Field::Text(reason.clone())),
+                    ],
+                },
+                DataUpdate::Banned(reason, unban_time) => vec![
+                    ("reason", Field::Text(reason.clone())),
+                    ("unban_time", Field::Real(unban_time.timestamp() as f64)),
+                ],
+                DataUpdate::ChangeFriendStatus(status) => {
+                    vec![("status", Field::Real(*status as f64))]
+                }
+                DataUpdate::Disconnected() => Vec::new(),
+                DataUpdate::Reconnecting() => {
+                    vec![("attempt", Field::Real(1.0)), ("delay", Field::Real(0.0))]
+                }
+                DataUpdate::FetchBdb(name, value) => {
+                    let mut fields = vec![("name", Field::Text(name.clone()))];
+                    if let Some(value) = value {
+                        fields.push(("value", Field::Bytes(value.clone())));
+                    }
+                    fields
+                }
+                DataUpdate::Kicked(reason) => vec![("reason", Field::Text(reason.clone()))],
+                DataUpdate::Login(code) => vec![("code", Field::Real(*code as u64 as f64))],
+                DataUpdate::LoginOk(pid, name) => vec![
+                    ("pid", Field::Real(*pid as f64)),
+                    ("name", Field::Text(name.clone())),
+                ],
+                DataUpdate::LoginBan(code, reason, unban_time) => vec![
+                    ("code", Field::Real(*code as u64 as f64)),
+                    ("reason", Field::Text(reason.clone())),
+                    ("unban_time", Field::Real(*unban_time as f64)),
+                ],
+                DataUpdate::P2P(sender, mid, payload) => vec![
+                    (
+                        "sender",
+                        Field::Real(sender.map(|sender| sender as f64).unwrap_or(-1.0)),
+                    ),
+                    ("message_id", Field::Real(*mid as f64)),
+                    (
+                        "payload",
+                        Field::Value(OptionalValue::Some(Value::Array(payload.clone()))),
+                    ),
+                ],
+                DataUpdate::Registration(code) => {
+                    vec![("code", Field::Real(*code as u64 as f64))]
+                }
+                DataUpdate::PlayerLoggedIn(pid, name, room) => vec![
+                    ("pid", Field::Real(*pid as f64)),
+                    ("name", Field::Text(name.clone())),
+                    ("room", Field::Text(room.clone())),
+                ],
+                DataUpdate::PlayerLoggedOut(pid) => vec![("pid", Field::Real(*pid as f64))],
+                DataUpdate::ServerMessage(message) => {
+                    vec![("message", Field::Text(message.clone()))]
+                }
+                DataUpdate::UpdateVariable(pid, name, value) => vec![
+                    ("pid", Field::Real(*pid as f64)),
+                    ("name", Field::Text(name.clone())),
+                    ("value", Field::Value(value.clone())),
+                    ("removed", removed(value)),
+                ],
+                DataUpdate::UpdateSyncVariable(pid, slot, name, value) => vec![
+                    ("pid", Field::Real(*pid as f64)),
+                    ("slot", Field::Real(*slot as f64)),
+                    ("name", Field::Text(name.clone())),
+                    ("value", Field::Value(value.clone())),
+                    ("removed", removed(value)),
+                ],
+                DataUpdate::UpdateSyncRemoval(pid, slot) => vec![
+                    ("pid", Field::Real(*pid as f64)),
+                    ("slot", Field::Real(*slot as f64)),
+                ],
+                DataUpdate::UpdateGameIni(file, section, key, value)
+                | DataUpdate::UpdatePlayerIni(file, section, key, value) => {
+                    let mut fields = Vec::with_capacity(5);
+                    if let Some(file) = file {
+                        fields.push(("file", Field::Text(file.clone())));
+                    }
+                    fields.extend([
+                        ("section", Field::Text(section.clone())),
+                        ("key", Field::Text(key.clone())),
+                        ("value", Field::Value(value.clone())),
+                        ("removed", removed(value)),
+                    ]);
+                    fields
+                }
+                DataUpdate::UpdateGameVersion(ver) => vec![("version", Field::Real(*ver))],
+                DataUpdate::UpdateAdministrator(pid, admin) => {
+                    let mut fields = vec![("pid", Field::Real(*pid as f64))];
+                    if let Some(admin) = admin {
+                        fields.extend([
+                            ("can_ban", Field::Real(admin.can_ban as u8 as f64)),
+                            ("can_unban", Field::Real(admin.can_unban as u8 as f64)),
+                            ("can_kick", Field::Real(admin.can_kick as u8 as f64)),
+                        ]);
+                    }
+                    fields
+                }
+                DataUpdate::ServerNotification(notif) => {
+                    vec![("message", Field::Text(notif.clone()))]
+                }
+                DataUpdate::LoginToken(token) => vec![("token", Field::Text(token.clone()))],
+            },
+            Notification::PlayerVariableRequest {
+                request,
+                pid,
+                name,
+                value,
+            }
+            | Notification::SyncVariableRequest {
+                request,
+                pid,
+                name,
+                value,
+            } => vec![
+                ("request", Field::Real(*request)),
+                ("pid", Field::Real(*pid as f64)),
+                ("name", Field::Text(name.clone())),
+                ("value", Field::Value(value.clone())),
+            ],
+            Notification::Ticket { id, result } => vec![
+                ("ticket", Field::Real(*id as f64)),
+                ("ok", Field::Real(if result.is_ok() { 1.0 } else { 0.0 })),
+                (
+                    "result",
+                    Field::Text(match result {
+                        Ok(result) | Err(result) => result.clone(),
+                    }),
+                ),
+            ],
+            Notification::ConnectResult(result) => vec![
+                ("ok", Field::Real(if result.is_ok() { 1.0 } else { 0.0 })),
+                (
+                    "category",
+                    Field::Text(String::from(
+                        result.as_ref().err().map_or("", |err| err.category.name()),
+                    )),
+                ),
+                (
+                    "message",
+                    Field::Text(
+                        result
+                            .as_ref()
+                            .err()
+                            .map(|err| err.message.clone())
+                            .unwrap_or_default(),
+                    ),
+                ),
+            ],
+            Notification::Reconnecting { attempt, delay } => vec![
+                ("attempt", Field::Real(*attempt as f64)),
+                ("delay", Field::Real(delay.as_millis() as f64)),
+            ],
+            Notification::ReconnectFailed { attempts } => {
+                vec![("attempts", Field::Real(*attempts as f64))]
+            }
+        }
+    }
+
+    /// Builds the `async_load` of the notification's Social async event.
+    fn to_ds_map(&self, handle: u64, seq: u64) -> Option<DsMap> {
+        let mut map = DsMap::new()?;
+        map.add_string("type", "crystal");
+        map.add_double("handle", handle as f64);
+        map.add_double("seq", seq as f64);
+        map.add_string("kind", self.kind());
+        for (key, field) in self.fields() {
+            match field {
+                Field::Real(value) => map.add_double(key, value),
+                Field::Text(value) => map.add_string(key, &value),
+                Field::Bytes(value) => map.add_string(key, &BASE64_STANDARD.encode(value)),
+                Field::Value(value) => map.add_string(key, &encode_optional_vari(&value, "!!")),
+            }
+        }
+        Some(map)
+    }
+}
+
+/// The queue of the instance the running export acts on.
+fn queue() -> ArcMutexGuard<RawMutex, Queue> {
+    instance::current().queue.lock_arc()
+}
+
+/// Pushes notifications to the queue of one instance, from any thread.
+#[derive(Clone)]
+pub(crate) struct Notifier(Arc<Mutex<Queue>>);
+
+/// A [`Notifier`] for the instance the running export acts on, to hand to its callbacks.
+pub(crate) fn notifier() -> Notifier {
+    Notifier(instance::current().queue.clone())
+}
+
+impl Notifier {
+    /// Delivers `notification` as an async event if enabled, otherwise queues it for polling.
+    ///
+    /// Must be called synchronously from wherever the notification originates, deferring it
+    /// (e.g. by spawning a task) would lose the order notifications arrived in.
+    ///
+    /// A coalesced notification takes the place, and the sequence number, of the value it
+    /// replaced.
+    pub fn push(&self, notification: Notification) {
+        push(&mut self.0.lock(), notification);
+    }
+
+    /// Drops every queued notification and starts the sequence numbers over at 1, keeping the
+    /// settings of the queue.
+    pub fn clear(&self) {
+        let mut queue = self.0.lock();
+        queue.entries.clear();
+        queue.pending.clear();
+        queue.dropped.clear();
+        queue.next_seq = 1;
+    }
+}
+
+fn push(queue: &mut Queue, notification: Notification) {
+    let Some(notification) = queue.coalesce(notification) else {
+        return;
+    };
+    if !queue.is_subscribed(notification.kind()) {
+        return;
+    }
+    let seq = queue.next_seq;
+    queue.next_seq += 1;
+    let key = pending_key(&notification).filter(|_| queue.coalescing && !queue.async_events);
+    if queue.deliver(seq, notification)
+        && let Some(key) = key
+    {
+        queue.pending.insert(key, seq);
+    }
+}
+
+/// Only keeps notifications of the given kinds, separated by `;`, or all of them if `kinds`
+/// is empty. Tickets are always kept.
+///
+/// Returns the unknown kinds, separated by `;`, and changes nothing if there are any.
+#[gm_func]
+pub fn __crystal_set_subscriptions(kinds: &str) -> String {
+    #[cfg(feature = "debug")]
+    println!("set_subscriptions({kinds:?})");
+    let mut subscriptions = BTreeSet::new();
+    let mut unknown = Vec::new();
+    for kind in kinds.split(';').filter(|kind| !kind.is_empty()) {
+        match KINDS.iter().find(|known| **known == kind) {
+            Some(kind) => {
+                subscriptions.insert(*kind);
+            }
+            None => unknown.push(kind),
+        }
+    }
+    if !unknown.is_empty() {
+        return unknown.join(";");
+    }
+    queue().subscriptions = (!subscriptions.is_empty()).then_some(subscriptions);
+    String::new()
+}
+
+/// The kinds that are currently kept, separated by `;`.
+#[gm_func]
+pub fn __crystal_get_subscriptions() -> String {
+    #[cfg(feature = "debug")]
+    println!("get_subscriptions()");
+    let queue = queue();
+    KINDS
+        .iter()
+        .filter(|kind| queue.is_subscribed(kind))
+        .copied()
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
+/// Called by Gamemaker when loading the extension, see [`async_event::register_callbacks`].
+#[gm_func("RegisterCallbacks")]
+pub fn register_callbacks(
+    create_async_event: *mut c_char,
+    ds_map_create: *mut c_char,
+    ds_map_add_double: *mut c_char,
+    ds_map_add_string: *mut c_char,
+) {
+    #[cfg(feature = "debug")]
+    println!("RegisterCallbacks()");
+    // SAFETY: Only Gamemaker calls this export, with the pointers it expects.
+    unsafe {
+        async_event::register_callbacks(
+            create_async_event,
+            ds_map_create,
+            ds_map_add_double,
+            ds_map_add_string,
+        )
+    };
+}
+
+/// Switches between Social async events and polling, returns whether async events are used.
+///
+/// Async events can't be enabled if Gamemaker never called `RegisterCallbacks`.
+#[gm_func]
+pub fn __crystal_set_async_events(enabled: f64) -> bool {
+    #[cfg(feature = "debug")]
+    println!("set_async_events({enabled:?})");
+    let enabled = enabled >= 0.5 && async_event::is_registered();
+    let mut queue = queue();
+    queue.async_events = enabled;
+    if enabled {
+        // Whatever was queued before switching still needs to reach the game, in order.
+        for (seq, notification) in std::mem::take(&mut queue.entries) {
+            queue.deliver(seq, notification);
+        }
+    }
+    enabled
+}
+
+#[gm_func]
+pub fn __crystal_get_notification() -> String {
+    #[cfg(feature = "debug")]
+    println!("get_notification()");
+    queue().pop().unwrap_or_default()
+}
+
+/// Pops every queued notification at once, separated by `\n`.
+#[gm_func]
+pub fn __crystal_get_notifications() -> String {
+    #[cfg(feature = "debug")]
+    println!("get_notifications()");
+    let mut queue = queue();
+    let mut res = String::new();
+    while let Some(text) = queue.pop() {
+        if !res.is_empty() {
+            res.push('\n');
+        }
+        res.push_str(&text);
+    }
+    res
+}
+
+/// Pops as many queued notifications as fit into `out` and returns how many were written.
+fn drain_into(out: &mut [u8], mut encode: impl FnMut(&mut Vec<u8>, u64, &Notification)) -> usize {
+    let mut queue = queue();
+    let (mut written, mut count) = (0, 0);
+    let mut record = Vec::new();
+    while let Some((seq, notification)) = queue.entries.front() {
+        record.clear();
+        encode(&mut record, *seq, notification);
+        let Some(dst) = out.get_mut(written..written + record.len()) else {
+            break;
+        };
+        dst.copy_from_slice(&record);
+        written += record.len();
+        count += 1;
+        queue.pop_entry();
+    }
+    count
+}
+
+/// Pops as many queued notifications as fit into `size` bytes of `buffer` and returns how many.
+///
+/// Each one is written as its byte length (u32, little-endian) followed by the polled text
+/// and a NUL byte, so it can be read with `buffer_u32` and `buffer_string`. A notification
+/// that doesn't fit stays queued, check `__crystal_get_notification_count` for a buffer that's
+/// too small to hold even one.
+#[gm_func]
+pub fn __crystal_drain_notifications(mut buffer: GmBuffer, size: f64) -> f64 {
+    #[cfg(feature = "debug")]
+    println!("drain_notifications({:?}, {size:?})", buffer.as_ptr());
+    // SAFETY: `buffer` is the address of a Gamemaker buffer of at least `size` bytes.
+    let out = unsafe { buffer.as_mut_slice(size.max(0.0) as usize) };
+    drain_into(out, |record, seq, notification| {
+        let text = format!("{seq};{}", notification.to_text());
+        record.extend_from_slice(&(text.len() as u32).to_le_bytes());
+        record.extend_from_slice(text.as_bytes());
+        record.push(0);
+    }) as f64
+}
+
+/// Same as `__crystal_drain_notifications`, but writes the records described in [`binary`].
+#[gm_func]
+pub fn __crystal_drain_notifications_binary(mut buffer: GmBuffer, size: f64) -> f64 {
+    #[cfg(feature = "debug")]
+    println!(
+        "drain_notifications_binary({:?}, {size:?})",
+        buffer.as_ptr()
+    );
+    // SAFETY: `buffer` is the address of a Gamemaker buffer of at least `size` bytes.
+    let out = unsafe { buffer.as_mut_slice(size.max(0.0) as usize) };
+    drain_into(out, binary::write_record) as f64
+}
+
+/// How many notifications are waiting to be polled.
+#[gm_func]
+pub fn __crystal_get_notification_count() -> f64 {
+    #[cfg(feature = "debug")]
+    println!("get_notification_count()");
+    queued() as f64
+}
+
+pub(crate) fn queued() -> usize {
+    queue().entries.len()
+}
+
+/// Caps the number of queued notifications, `0` removes the cap.
+///
+/// `policy` decides what's dropped once the cap is reached: `0` the oldest notification,
+/// `1` the incoming one, `2` the oldest one superseded by the incoming one (falling back to
+/// the oldest). Returns `false` and changes nothing if `policy` is none of those.
+#[gm_func]
+pub fn __crystal_set_notification_limit(limit: f64, policy: f64) -> bool {
+    #[cfg(feature = "debug")]
+    println!("set_notification_limit({limit:?}, {policy:?})");
+    let Some(policy) = OverflowPolicy::from_f64(policy) else {
+        return false;
+    };
+    let mut queue = queue();
+    queue.limit = limit.max(0.0) as usize;
+    queue.policy = policy;
+    queue.truncate();
+    true
+}
+
+/// Caps the number of queued notifications of every instance like
+/// `__crystal_set_notification_limit`, keeping the policy.
+pub(crate) fn set_limit(limit: usize) {
+    DEFAULTS.lock().0 = limit;
+    for instance in instance::all() {
+        let mut queue = instance.queue.lock();
+        queue.limit = limit;
+        queue.truncate();
+    }
+}
+
+/// Sets the overflow policy of every instance by name (`drop_oldest`, `drop_newest` or
+/// `coalesce`) or number, keeping the limit. Returns whether `policy` is known.
+pub(crate) fn set_policy(policy: &str) -> bool {
+    let Some(policy) = OverflowPolicy::from_name(policy) else {
+        return false;
+    };
+    DEFAULTS.lock().1 = policy;
+    for instance in instance::all() {
+        let mut queue = instance.queue.lock();
+        queue.policy = policy;
+        queue.truncate();
+    }
+    true
+}
+
+/// How many notifications were dropped so far, as `kind:count` pairs separated by `;`.
+#[gm_func]
+pub fn __crystal_get_dropped_notifications() -> String {
+    #[cfg(feature = "debug")]
+    println!("get_dropped_notifications()");
+    queue()
+        .dropped
+        .iter()
+        .map(|(kind, count)| format!("{kind}:{count}"))
+        .collect::<Vec<_>>()
+        .join(";")
+}
+
+/// Toggles coalescing of queued `update_variable` and `update_sync_variable` values.
+///
+/// While enabled, a new value for a variable replaces its pending one instead of being queued
+/// after it, so only the latest value is delivered. This only applies to polling.
+#[gm_func]
+pub fn __crystal_set_coalescing(enabled: f64) {
+    #[cfg(feature = "debug")]
+    println!("set_coalescing({enabled:?})");
+    let mut queue = queue();
+    queue.coalescing = enabled >= 0.5;
+    if !queue.coalescing {
+        queue.pending.clear();
+    }
+}
+
+#[gm_func]
+pub fn __crystal_reset_dropped_notifications() {
+    #[cfg(feature = "debug")]
+    println!("reset_dropped_notifications()");
+    queue().dropped.clear();
+}
+
+#[cfg(test)]
+mod tests {
+    use gm_utils::func::GmArg;
+
+    use super::*;
+
+    fn variable(name: &str, value: Option<i64>) -> Notification {
+        let value = value.map_or(OptionalValue::None, |value| {
+            OptionalValue::Some(Value::Int(value))
+        });
+        Notification::Data(DataUpdate::UpdateVariable(1, String::from(name), value))
+    }
+
+    fn sync_variable(slot: usize, name: &str, value: i64) -> Notification {
+        Notification::Data(DataUpdate::UpdateSyncVariable(
+            1,
+            slot,
+            String::from(name),
+            OptionalValue::Some(Value::Int(value)),
+        ))
+    }
+
+    /// The sequence number, kind and integer value, if any, of every queued notification.
+    fn entries(queue: &Queue) -> Vec<(u64, &'static str, Option<i64>)> {
+        queue
+            .entries
+            .iter()
+            .map(|(seq, notification)| {
+                let value = match notification {
+                    Notification::Data(
+                        DataUpdate::UpdateVariable(_, _, OptionalValue::Some(Value::Int(value)))
+                        | DataUpdate::UpdateSyncVariable(
+                            _,
+                            _,
+                            _,
+                            OptionalValue::Some(Value::Int(value)),
+                        ),
+                    ) => Some(*value),
+                    _ => None,
+                };
+                (*seq, notification.kind(), value)
+            })
+            .collect()
+    }
+
+    fn coalescing(limit: usize, policy: OverflowPolicy) -> Queue {
+        let mut queue = Queue::new(0);
+        queue.coalescing = true;
+        queue.limit = limit;
+        queue.policy = policy;
+        queue
+    }
+
+    #[test]
+    fn removals_split_coalesced_values() {
+        let mut queue = coalescing(0, OverflowPolicy::DropOldest);
+        push(&mut queue, variable("a", Some(1)));
+        push(&mut queue, variable("a", Some(2)));
+        push(&mut queue, variable("a", None));
+        push(&mut queue, variable("a", Some(3)));
+        push(&mut queue, variable("a", Some(4)));
+        assert_eq!(
+            entries(&queue),
+            [
+                (1, "update_variable", Some(2)),
+                (2, "update_variable", None),
+                (3, "update_variable", Some(4)),
+            ]
+        );
+
+        let mut queue = coalescing(0, OverflowPolicy::DropOldest);
+        push(&mut queue, sync_variable(0, "a", 1));
+        push(&mut queue, sync_variable(1, "a", 1));
+        push(
+            &mut queue,
+            Notification::Data(DataUpdate::UpdateSyncRemoval(1, 0)),
+        );
+        push(&mut queue, sync_variable(0, "a", 2));
+        push(&mut queue, sync_variable(1, "a", 2));
+        assert_eq!(
+            entries(&queue),
+            [
+                (1, "update_sync_variable", Some(1)),
+                (2, "update_sync_variable", Some(2)),
+                (3, "update_sync_removal", None),
+                (4, "update_sync_variable", Some(2)),
+            ]
+        );
+    }
+
+    #[test]
+    fn dropped_values_are_not_coalesced_into() {
+        let mut queue = coalescing(1, OverflowPolicy::DropNewest);
+        push(&mut queue, variable("a", Some(1)));
+        push(&mut queue, variable("b", Some(1)));
+        assert!(!queue.pending.contains_key(&(1, None, String::from("b"))));
+        push(&mut queue, variable("a", Some(2)));
+        assert_eq!(entries(&queue), [(1, "update_variable", Some(2))]);
+        assert_eq!(queue.dropped, BTreeMap::from([("update_variable", 1)]));
+
+        assert!(queue.pop_entry().is_some());
+        push(&mut queue, variable("b", Some(2)));
+        push(&mut queue, variable("b", Some(3)));
+        assert_eq!(entries(&queue), [(3, "update_variable", Some(3))]);
+    }
+
+    fn bounded(limit: usize, policy: OverflowPolicy) -> Queue {
+        let mut queue = Queue::new(0);
+        queue.limit = limit;
+        queue.policy = policy;
+        queue
+    }
+
+    #[test]
+    fn full_queues_drop_by_policy() {
+        let mut queue = bounded(2, OverflowPolicy::DropOldest);
+        for name in ["a", "b", "c"] {
+            push(&mut queue, variable(name, Some(1)));
+        }
+        assert_eq!(
+            entries(&queue),
+            [
+                (2, "update_variable", Some(1)),
+                (3, "update_variable", Some(1))
+            ]
+        );
+
+        let mut queue = bounded(2, OverflowPolicy::DropNewest);
+        for name in ["a", "b", "c"] {
+            push(&mut queue, variable(name, Some(1)));
+        }
+        assert_eq!(
+            entries(&queue),
+            [
+                (1, "update_variable", Some(1)),
+                (2, "update_variable", Some(1))
+            ]
+        );
+
+        let mut queue = bounded(2, OverflowPolicy::Coalesce);
+        push(&mut queue, variable("a", Some(1)));
+        push(&mut queue, variable("b", Some(1)));
+        push(&mut queue, variable("a", Some(2)));
+        assert_eq!(
+            entries(&queue),
+            [
+                (2, "update_variable", Some(1)),
+                (3, "update_variable", Some(2))
+            ]
+        );
+        // Nothing queued is superseded by a disconnect, so the oldest one goes.
+        push(&mut queue, Notification::Data(DataUpdate::Disconnected()));
+        assert_eq!(
+            entries(&queue),
+            [(3, "update_variable", Some(2)), (4, "disconnected", None)]
+        );
+        assert_eq!(queue.dropped, BTreeMap::from([("update_variable", 2)]));
+    }
+
+    #[test]
+    fn lowering_the_limit_drops_and_counts() {
+        instance::scratch(|| {
+            let notifier = notifier();
+            for value in 1..=4 {
+                notifier.push(variable("a", Some(value)));
+            }
+            notifier.push(Notification::Data(DataUpdate::Disconnected()));
+            assert!(!__crystal_set_notification_limit(2.0, 3.0));
+            assert_eq!(queued(), 5);
+
+            assert!(__crystal_set_notification_limit(3.0, 1.0));
+            assert_eq!(
+                entries(&queue()),
+                [
+                    (1, "update_variable", Some(1)),
+                    (2, "update_variable", Some(2)),
+                    (3, "update_variable", Some(3)),
+                ]
+            );
+            assert!(__crystal_set_notification_limit(1.0, 0.0));
+            assert_eq!(entries(&queue()), [(3, "update_variable", Some(3))]);
+            assert_eq!(
+                __crystal_get_dropped_notifications(),
+                "disconnected:1;update_variable:3"
+            );
+
+            __crystal_reset_dropped_notifications();
+            assert_eq!(__crystal_get_dropped_notifications(), "");
+            notifier.push(variable("a", Some(5)));
+            assert_eq!(__crystal_get_dropped_notifications(), "update_variable:1");
+        });
+    }
+
+    /// Drains into a buffer of `size` bytes, returning the polled texts it read back.
+    fn drain(size: usize) -> Vec<String> {
+        let mut out = vec![0xff; size];
+        // SAFETY: `out` is `size` bytes long.
+        let buffer = unsafe { GmBuffer::to_arg(out.as_mut_ptr().cast()) };
+        let count = __crystal_drain_notifications(buffer, size as f64);
+        let mut texts = Vec::new();
+        let mut rest = &out[..];
+        while texts.len() < count as usize {
+            let (len, tail) = rest.split_first_chunk().unwrap();
+            let len = u32::from_le_bytes(*len) as usize;
+            assert_eq!(tail[len], 0);
+            texts.push(String::from_utf8(tail[..len].to_vec()).unwrap());
+            rest = &tail[len + 1..];
+        }
+        assert!(rest.iter().all(|byte| *byte == 0xff), "{rest:?}");
+        texts
+    }
+
+    fn seqs(texts: &[String]) -> Vec<u64> {
+        texts
+            .iter()
+            .map(|text| text.split_once(';').unwrap().0.parse().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn drops_leave_gaps_in_seqs() {
+        instance::scratch(|| {
+            let notifier = notifier();
+            assert!(__crystal_set_notification_limit(3.0, 0.0));
+            for _ in 0..5 {
+                notifier.push(Notification::Data(DataUpdate::Disconnected()));
+            }
+            assert_eq!(seqs(&drain(1024)), [3, 4, 5]);
+
+            assert!(__crystal_set_notification_limit(2.0, 1.0));
+            for _ in 0..3 {
+                notifier.push(Notification::Data(DataUpdate::Disconnected()));
+            }
+            assert_eq!(seqs(&drain(1024)), [6, 7]);
+            notifier.push(Notification::Data(DataUpdate::Disconnected()));
+            notifier.push(Notification::Data(DataUpdate::Disconnected()));
+            assert_eq!(seqs(&drain(1024)), [9, 10]);
+        });
+    }
+
+    #[test]
+    fn drains_what_fits() {
+        instance::scratch(|| {
+            let notifier = notifier();
+            for _ in 0..4 {
+                notifier.push(Notification::Data(DataUpdate::Disconnected()));
+            }
+            // Each record is 4 bytes of length, 14 of text and a NUL.
+            assert_eq!(drain(18), [] as [String; 0]);
+            assert_eq!(drain(19), ["1;disconnected"]);
+            assert_eq!(drain(56), ["2;disconnected", "3;disconnected"]);
+            assert_eq!(__crystal_get_notification_count(), 1.0);
+
+            notifier.push(Notification::Data(DataUpdate::Kicked(String::from("bye"))));
+            assert_eq!(drain(1024), ["4;disconnected", "5;kicked;Ynll"]);
+            assert_eq!(__crystal_get_notification_count(), 0.0);
+            assert_eq!(drain(1024), [] as [String; 0]);
+        });
+    }
+
+    #[test]
+    fn subscriptions_filter_kinds() {
+        instance::scratch(|| {
+            assert_eq!(__crystal_set_subscriptions("kicked;disconnected"), "");
+            assert_eq!(__crystal_get_subscriptions(), "disconnected;kicked;ticket");
+            assert_eq!(
+                __crystal_set_subscriptions("kicked;nope;;login_ok;Kicked"),
+                "nope;Kicked"
+            );
+            assert_eq!(__crystal_get_subscriptions(), "disconnected;kicked;ticket");
+
+            let notifier = notifier();
+            notifier.push(Notification::Data(DataUpdate::LoginOk(
+                1,
+                String::from("a"),
+            )));
+            notifier.push(Notification::Data(DataUpdate::Kicked(String::new())));
+            notifier.push(Notification::Ticket {
+                id: 1,
+                result: Ok(String::new()),
+            });
+            notifier.push(variable("a", Some(1)));
+            notifier.push(Notification::Data(DataUpdate::Disconnected()));
+            assert_eq!(
+                entries(&queue()),
+                [
+                    (1, "kicked", None),
+                    (2, "ticket", None),
+                    (3, "disconnected", None)
+                ]
+            );
+
+            assert_eq!(__crystal_set_subscriptions(""), "");
+            assert_eq!(__crystal_get_subscriptions(), KINDS.join(";"));
+        });
+        assert_eq!(BTreeSet::from(KINDS).len(), KINDS.len());
+    }
+}
@@ -0,0 +1,397 @@
+//! Reconnecting by itself after the connection dropped.
+//!
+//! Off by default, `__crystal_set_reconnect_policy` picks how each instance retries: at a fixed
+//! interval or with a delay doubling after each attempt, up to a number of attempts. Only a
+//! connection which was established (see `connect_result`) and dropped without
+//! `__crystal_disconnect` is retried.
+//!
+//! Before each attempt a `reconnecting` notification tells its number and how long until it's
+//! made, and each attempt ends with a `connect_result` like a `__crystal_connect`. Once the
+//! attempts ran out, `reconnect_failed` tells how many were made. While a policy is set, the
+//! server moving the client to another host isn't reported as a `reconnecting` of its own.
+//!
+//! The client keeps the room, variables and syncs across connections and sends them when
+//! logging in. So once reconnected, the session is sent again and a player who was logged in
+//! is logged in again, with the last login token the server handed out or else the password
+//! they logged in with, which restores the rest. The `login` notification tells how that went.
+
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    sync::{
+        Arc, Weak,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+    },
+    time::Duration,
+};
+
+use crystal_server::types::DataUpdate;
+use gm_utils::gm_func;
+use parking_lot::Mutex;
+
+use crate::{
+    connection,
+    instance::{self, Instance},
+    notifications::{Notification, Notifier},
+};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum Strategy {
+    #[default]
+    Off = 0,
+    Fixed = 1,
+    Exponential = 2,
+}
+
+impl Strategy {
+    fn from_f64(strategy: f64) -> Option<Self> {
+        match strategy as i64 {
+            0 => Some(Self::Off),
+            1 => Some(Self::Fixed),
+            2 => Some(Self::Exponential),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Policy {
+    strategy: Strategy,
+    delay: Duration,
+    /// `ZERO` means uncapped.
+    max_delay: Duration,
+    /// `0` means unlimited.
+    max_attempts: u32,
+}
+
+impl Policy {
+    /// How long to wait before attempt `attempt`, counted from 1, or `None` if it's not made.
+    ///
+    /// `jitter` is between 0 and 1 and picks how much of the upper half of an exponential delay
+    /// is waited, so clients which lost their connection together don't all come back at once.
+    fn delay(&self, attempt: u32, jitter: f64) -> Option<Duration> {
+        if self.max_attempts != 0 && attempt > self.max_attempts {
+            return None;
+        }
+        match self.strategy {
+            Strategy::Off => None,
+            Strategy::Fixed => Some(self.delay),
+            Strategy::Exponential => {
+                let mut delay = self
+                    .delay
+                    .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)));
+                if !self.max_delay.is_zero() {
+                    delay = delay.min(self.max_delay);
+                }
+                Some(delay / 2 + (delay / 2).mul_f64(jitter))
+            }
+        }
+    }
+}
+
+/// A number between 0 and 1, different each call.
+fn jitter() -> f64 {
+    // Each `RandomState` is seeded anew, which is random enough to spread delays.
+    let bits = RandomState::new().build_hasher().finish();
+    (bits >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// Ids of the attempt loops, `0` stands for none.
+static NEXT_RUN: AtomicU64 = AtomicU64::new(1);
+
+/// The reconnect policy of an instance and what it needs to restore a connection.
+#[derive(Default)]
+pub(crate) struct Reconnect {
+    policy: Mutex<Policy>,
+    /// Whether the connection should be kept, i.e. it was established and not dropped on
+    /// purpose since.
+    wanted: AtomicBool,
+    /// The id of the loop making attempts, `0` if there's none.
+    run: AtomicU64,
+    /// The login being made, which becomes `login` once the server accepted it.
+    attempt: Mutex<Option<Login>>,
+    /// How the player is logged in.
+    login: Mutex<Option<Login>>,
+}
+
+/// A name and what proves it's the player's, to log in again with.
+#[derive(Clone)]
+struct Login {
+    name: String,
+    secret: Secret,
+}
+
+#[derive(Clone)]
+pub(crate) enum Secret {
+    Password(String),
+    Token(String),
+}
+
+impl Reconnect {
+    /// Keeps the connection which was just established.
+    pub fn established(&self) {
+        self.wanted.store(true, Ordering::Relaxed);
+    }
+
+    /// Lets the connection go, stopping any attempts.
+    pub fn stop(&self) {
+        self.wanted.store(false, Ordering::Relaxed);
+        self.run.store(0, Ordering::Relaxed);
+    }
+
+    /// Stops reconnecting and forgets the login, for a client that starts over.
+    pub fn reset(&self) {
+        self.stop();
+        self.forget_login();
+    }
+
+    /// Remembers how the player is logging in, to log in the same way after reconnecting
+    /// once the server accepted it.
+    pub fn logging_in(&self, name: &str, secret: Secret) {
+        *self.attempt.lock() = Some(Login {
+            name: name.to_owned(),
+            secret,
+        });
+    }
+
+    /// Forgets the login, so reconnecting won't log in again.
+    pub fn forget_login(&self) {
+        *self.attempt.lock() = None;
+        *self.login.lock() = None;
+    }
+
+    fn running(&self, run: u64) -> bool {
+        self.run.load(Ordering::Relaxed) == run
+    }
+}
+
+/// Remembers logins from `update`, returns whether it dropped a connection which should be
+/// restored by [`start`]ing to reconnect.
+pub(crate) fn observe(instance: &Weak<Instance>, update: &DataUpdate) -> bool {
+    let Some(instance) = instance.upgrade() else {
+        return false;
+    };
+    let reconnect = &instance.reconnect;
+    match update {
+        DataUpdate::LoginOk(_, name) => {
+            let attempt = reconnect.attempt.lock().take();
+            *reconnect.login.lock() = attempt.map(|login| Login {
+                name: name.clone(),
+                ..login
+            });
+        }
+        DataUpdate::LoginToken(token) => {
+            if let Some(login) = &mut *reconnect.login.lock() {
+                login.secret = Secret::Token(token.clone());
+            }
+        }
+        DataUpdate::Disconnected() => {
+            return reconnect.wanted.load(Ordering::Relaxed)
+                && reconnect.policy.lock().strategy != Strategy::Off
+                && reconnect.run.load(Ordering::Relaxed) == 0;
+        }
+        _ => (),
+    }
+    false
+}
+
+/// Whether `update` is left out of the notifications: while a policy is set, its attempts are
+/// reported instead of the client's own reconnecting to another host.
+pub(crate) fn superseded(instance: &Weak<Instance>, update: &DataUpdate) -> bool {
+    matches!(update, DataUpdate::Reconnecting())
+        && instance
+            .upgrade()
+            .is_some_and(|instance| instance.reconnect.policy.lock().strategy != Strategy::Off)
+}
+
+/// Makes attempts to reconnect `instance` as its policy says, from a client callback.
+pub(crate) fn start(instance: &Weak<Instance>, notifier: &Notifier) {
+    let Some(strong) = instance.upgrade() else {
+        return;
+    };
+    let run = NEXT_RUN.fetch_add(1, Ordering::Relaxed);
+    if strong
+        .reconnect
+        .run
+        .compare_exchange(0, run, Ordering::Relaxed, Ordering::Relaxed)
+        .is_err()
+    {
+        return;
+    }
+    tokio::spawn(attempts(instance.clone(), notifier.clone(), run));
+}
+
+async fn attempts(instance: Weak<Instance>, notifier: Notifier, run: u64) {
+    let mut attempt = 0;
+    loop {
+        let Some(delay) = instance.upgrade().and_then(|instance| {
+            let reconnect = &instance.reconnect;
+            if !reconnect.running(run) {
+                return None;
+            }
+            attempt += 1;
+            let delay = reconnect.policy.lock().delay(attempt, jitter());
+            if delay.is_none() {
+                reconnect.stop();
+                notifier.push(Notification::ReconnectFailed {
+                    attempts: attempt - 1,
+                });
+            }
+            delay
+        }) else {
+            return;
+        };
+        notifier.push(Notification::Reconnecting { attempt, delay });
+        tokio::time::sleep(delay).await;
+        let Some(instance) = instance.upgrade() else {
+            return;
+        };
+        if !instance.reconnect.running(run) {
+            return;
+        }
+        let result = connection::attempt(instance.clone(), notifier.clone()).await;
+        if connection::established(&result) && instance.reconnect.running(run) {
+            let _ = instance.reconnect.run.compare_exchange(
+                run,
+                0,
+                Ordering::Relaxed,
+                Ordering::Relaxed,
+            );
+            restore(&instance).await;
+            return;
+        }
+    }
+}
+
+/// Sends the session again and logs the player in again, if they were logged in.
+async fn restore(instance: &Arc<Instance>) {
+    let login = instance.reconnect.login.lock().clone();
+    let _ = instance
+        .client
+        .get()
+        .run(move |crystal| {
+            Box::pin(async move {
+                let session = crystal.get_session().await;
+                if !session.is_empty() {
+                    let _ = crystal.set_session(&session).await;
+                }
+                let Some(Login { name, secret }) = login else {
+                    return;
+                };
+                let _ = match secret {
+                    Secret::Password(password) => crystal.login(&name, &password).await,
+                    Secret::Token(token) => crystal.login_with_token(&name, &token).await,
+                };
+            })
+        })
+        .await;
+}
+
+/// Sets how the client reconnects after its connection dropped.
+///
+/// `strategy` `0` doesn't, `1` retries every `delay` milliseconds and `2` waits `delay`
+/// milliseconds before the first retry and twice as long before each next one, up to
+/// `max_delay` (`0` for no cap), of which up to half is left out at random. `max_attempts`
+/// caps the retries, `0` for no cap. Returns `false` and changes nothing if `strategy` is none
+/// of those or a number is negative.
+#[gm_func]
+pub fn __crystal_set_reconnect_policy(
+    strategy: f64,
+    delay: f64,
+    max_delay: f64,
+    max_attempts: f64,
+) -> bool {
+    #[cfg(feature = "debug")]
+    println!("set_reconnect_policy({strategy:?}, {delay:?}, {max_delay:?}, {max_attempts:?})");
+    let Some(strategy) = Strategy::from_f64(strategy) else {
+        return false;
+    };
+    let millis = |ms: f64| Duration::try_from_secs_f64(ms / 1000.0).ok();
+    let (Some(delay), Some(max_delay)) = (millis(delay), millis(max_delay)) else {
+        return false;
+    };
+    if max_attempts.is_nan() || max_attempts < 0.0 {
+        return false;
+    }
+    *instance::current().reconnect.policy.lock() = Policy {
+        strategy,
+        delay,
+        max_delay,
+        max_attempts: max_attempts as u32,
+    };
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delays_follow_the_policy() {
+        let secs = Duration::from_secs;
+        let mut policy = Policy {
+            strategy: Strategy::Fixed,
+            delay: secs(2),
+            max_delay: secs(10),
+            max_attempts: 3,
+        };
+        assert_eq!(policy.delay(1, 0.5), Some(secs(2)));
+        assert_eq!(policy.delay(3, 0.5), Some(secs(2)));
+        assert_eq!(policy.delay(4, 0.5), None);
+
+        policy.strategy = Strategy::Exponential;
+        policy.max_attempts = 0;
+        let delays = (1..=5)
+            .map(|attempt| policy.delay(attempt, 1.0).unwrap().as_secs())
+            .collect::<Vec<_>>();
+        assert_eq!(delays, [2, 4, 8, 10, 10]);
+        assert_eq!(policy.delay(3, 0.0), Some(secs(4)));
+        assert_eq!(policy.delay(u32::MAX, 1.0), Some(secs(10)));
+        policy.max_delay = Duration::ZERO;
+        assert_eq!(policy.delay(6, 1.0), Some(secs(64)));
+
+        policy.strategy = Strategy::Off;
+        assert_eq!(policy.delay(1, 0.5), None);
+        for _ in 0..100 {
+            assert!((0.0..1.0).contains(&jitter()));
+        }
+    }
+
+    #[test]
+    fn accepted_logins_are_kept() {
+        instance::scratch(|| {
+            let instance = instance::current();
+            let weak = Arc::downgrade(&instance);
+            let reconnect = &instance.reconnect;
+            let login_ok = DataUpdate::LoginOk(1, String::from("Name"));
+            let kept = || {
+                reconnect
+                    .login
+                    .lock()
+                    .clone()
+                    .map(|Login { name, secret }| match secret {
+                        Secret::Password(password) => (name, "password", password),
+                        Secret::Token(token) => (name, "token", token),
+                    })
+            };
+
+            reconnect.logging_in("name", Secret::Password(String::from("hunter2")));
+            assert!(kept().is_none());
+            observe(&weak, &login_ok);
+            let password = (String::from("Name"), "password", String::from("hunter2"));
+            assert_eq!(kept(), Some(password));
+            observe(&weak, &DataUpdate::LoginToken(String::from("token")));
+            let token = (String::from("Name"), "token", String::from("token"));
+            assert_eq!(kept(), Some(token));
+
+            reconnect.forget_login();
+            observe(&weak, &login_ok);
+            assert!(kept().is_none());
+
+            assert!(!superseded(&weak, &DataUpdate::Reconnecting()));
+            assert!(__crystal_set_reconnect_policy(1.0, 100.0, 0.0, 0.0));
+            assert!(superseded(&weak, &DataUpdate::Reconnecting()));
+            assert!(!superseded(&weak, &DataUpdate::Disconnected()));
+        });
+    }
+}
@@ -0,0 +1,1043 @@
+//! Non-blocking variants of the crystal exports.
+//!
+//! Every `__crystal_*_async` export returns a ticket id right away and runs the call on
+//! [`RUNTIME`]. Once it finishes, a `ticket;{id};{ok};{result}` notification is queued,
+//! where `ok` is `1` or `0` and `result` is the base64 of either the value the blocking
+//! export would have returned or the error message.
+
+use std::{
+    fmt::Display,
+    future::Future,
+    panic::AssertUnwindSafe,
+    pin::pin,
+    sync::atomic::{AtomicU64, AtomicUsize, Ordering},
+    thread,
+};
+
+use base64::{Engine, prelude::BASE64_STANDARD};
+use chrono::DateTime;
+use crystal_server::types::SyncType;
+use futures_util::{FutureExt, StreamExt, pin_mut};
+use gm_utils::gm_func;
+use tokio::sync::Notify;
+
+use crate::{
+    RUNTIME,
+    config::{self, TimedOut},
+    decode_payload, decode_player_request, decode_vari, encode_achievement, encode_administrator,
+    encode_friends, encode_highscore, encode_list, encode_none, encode_player, encode_synciter,
+    encode_vari, get_achievement, get_reached_achievement,
+    instance::{self, client},
+    notifications::{self, Notification},
+    reach_achievement,
+    reconnect::Secret,
+};
+
+static NEXT_TICKET: AtomicU64 = AtomicU64::new(1);
+static IN_FLIGHT: AtomicUsize = AtomicUsize::new(0);
+static DRAINED: Notify = Notify::const_new();
+
+/// Counts a running ticket until dropped, which also happens when the runtime stops first.
+struct InFlight;
+
+impl InFlight {
+    fn new() -> Self {
+        IN_FLIGHT.fetch_add(1, Ordering::AcqRel);
+        Self
+    }
+}
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        if IN_FLIGHT.fetch_sub(1, Ordering::AcqRel) == 1 {
+            DRAINED.notify_waiters();
+        }
+    }
+}
+
+/// Resolves once no ticket is running anymore.
+pub(crate) async fn drained() {
+    loop {
+        let mut notified = pin!(DRAINED.notified());
+        notified.as_mut().enable();
+        if IN_FLIGHT.load(Ordering::Acquire) == 0 {
+            return;
+        }
+        notified.await;
+    }
+}
+
+/// Converts the outcome of a crystal call into the payload of a `ticket` notification.
+///
+/// The `Ok` payload uses the same encoding as the return value of the blocking export.
+trait TicketResult {
+    fn into_ticket(self) -> Result<String, String>;
+}
+
+impl TicketResult for () {
+    fn into_ticket(self) -> Result<String, String> {
+        Ok(String::new())
+    }
+}
+
+impl TicketResult for bool {
+    fn into_ticket(self) -> Result<String, String> {
+        Ok(String::from(if self { "1" } else { "0" }))
+    }
+}
+
+impl TicketResult for f64 {
+    fn into_ticket(self) -> Result<String, String> {
+        Ok(self.to_string())
+    }
+}
+
+impl TicketResult for String {
+    fn into_ticket(self) -> Result<String, String> {
+        Ok(self)
+    }
+}
+
+impl<T: TicketResult, E: Display> TicketResult for Result<T, E> {
+    fn into_ticket(self) -> Result<String, String> {
+        self.map_err(|e| e.to_string())?.into_ticket()
+    }
+}
+
+/// The payload of a ticket whose call returned, panicked or timed out.
+fn outcome<T: TicketResult>(
+    outcome: Result<thread::Result<T>, TimedOut>,
+) -> Result<String, String> {
+    match outcome {
+        Ok(Ok(result)) => result.into_ticket(),
+        Ok(Err(_)) => Err(String::from("the call panicked")),
+        Err(TimedOut) => Err(String::from("the call timed out")),
+    }
+}
+
+/// Spawns `fut` on the runtime and returns the ticket id its result will be reported under.
+///
+/// The result is queued for the instance the export acts on, ticket ids are unique across all.
+fn ticket<T: TicketResult>(fut: impl Future<Output = T> + Send + 'static) -> f64 {
+    let id = NEXT_TICKET.fetch_add(1, Ordering::Relaxed);
+    let in_flight = InFlight::new();
+    let notifier = notifications::notifier();
+    RUNTIME.get().spawn(async move {
+        let result = outcome(config::with_timeout(AssertUnwindSafe(fut).catch_unwind()).await);
+        notifier.push(Notification::Ticket { id, result });
+        drop(in_flight);
+    });
+    id as f64
+}
+
+/// Returns a ticket that fails with `err`, for calls rejected before reaching the client.
+fn rejected(err: impl Display) -> f64 {
+    ticket(std::future::ready(Err::<(), String>(err.to_string())))
+}
+
+#[gm_func]
+pub fn __crystal_update_async() -> f64 {
+    #[cfg(feature = "debug")]
+    println!("update_async()");
+    ticket(client().run(move |crystal| Box::pin(async move { crystal.update().await })))
+}
+
+#[gm_func]
+pub fn __crystal_is_connected_async() -> f64 {
+    #[cfg(feature = "debug")]
+    println!("is_connected_async()");
+    ticket(client().run(move |crystal| Box::pin(async move { crystal.is_connected().await })))
+}
+
+#[gm_func]
+pub fn __crystal_is_connecting_async() -> f64 {
+    #[cfg(feature = "debug")]
+    println!("is_connecting_async()");
+    ticket(client().run(move |crystal| Box::pin(async move { crystal.is_connecting().await })))
+}
+
+#[gm_func]
+pub fn __crystal_is_loggedin_async() -> f64 {
+    #[cfg(feature = "debug")]
+    println!("is_loggedin_async()");
+    ticket(client().run(move |crystal| Box::pin(async move { crystal.is_loggedin().await })))
+}
+
+#[gm_func]
+pub fn __crystal_get_ping_async() -> f64 {
+    #[cfg(feature = "debug")]
+    println!("get_ping_async()");
+    ticket(client().run(move |crystal| Box::pin(async move { crystal.get_ping().await })))
+}
+
+#[gm_func]
+pub fn __crystal_set_game_token_async(token: &str) -> f64 {
+    #[cfg(feature = "debug")]
+    println!("set_game_token_async({token:?})");
+    let token = token.to_owned();
+    ticket(
+        client().run(move |crystal| Box::pin(async move { crystal.set_game_token(&token).await })),
+    )
+}
+
+#[gm_func]
+pub fn __crystal_disconnect_async() -> f64 {
+    #[cfg(feature = "debug")]
+    println!("disconnect_async()");
+    instance::current().reconnect.stop();
+    ticket(client().run(move |crystal| Box::pin(async move { crystal.disconnect().await })))
+}
+
+#[gm_func]
+pub fn __crystal_login_async(name: &str, passw: &str) -> f64 {
+    #[cfg(feature = "debug")]
+    println!("login_async({name:?}, {passw:?})");
+    instance::current()
+        .reconnect
+        .logging_in(name, Secret::Password(passw.to_owned()));
+    let (name, passw) = (name.to_owned(), passw.to_owned());
+    ticket(client().run(move |crystal| Box::pin(async move { crystal.login(&name, &passw).await })))
+}
+
+#[gm_func]
+pub fn __crystal_login_with_token_async(name: &str, token: &str) -> f64 {
+    #[cfg(feature = "debug")]
+    println!("login_with_token_async({name:?}, {token:?})");
+    instance::current()
+        .reconnect
+        .logging_in(name, Secret::Token(token.to_owned()));
+    let (name, token) = (name.to_owned(), token.to_owned());
+    ticket(
+        client().run(move |crystal| {
+            Box::pin(async move { crystal.login_with_token(&name, &token).await })
+        }),
+    )
+}
+
+#[gm_func]
+pub fn __crystal_register_async(name: &str, email: &str, passw: &str, repeat_passw: &str) -> f64 {
+    #[cfg(feature = "debug")]
+    println!("register_async({name:?}, {email:?}, {passw:?}, {repeat_passw:?})");
+    let (name, email, passw, repeat_passw) = (
+        name.to_owned(),
+        email.to_owned(),
+        passw.to_owned(),
+        repeat_passw.to_owned(),
+    );
+    ticket(client().run(move |crystal| {
+        Box::pin(async move { crystal.register(&name, &email, &passw, &repeat_passw).await })
+    }))
+}
+
+#[gm_func]
+pub fn __crystal_get_player_id_async() -> f64 {
+    #[cfg(feature = "debug")]
+    println!("get_player_id_async()");
+    ticket(client().run(move |crystal| {
+        Box::pin(async move {
+            crystal
+                .get_player_id()
+                .await
+                .map(|val| val as f64)
+                .unwrap_or(-1.0)
+        })
+    }))
+}
+
+#[gm_func]
+pub fn __crystal_get_player_name_async() -> f64 {
+    #[cfg(feature = "debug")]
+    println!("get_player_name_async()");
+    ticket(client().run(move |crystal| {
+        Box::pin(async move { crystal.get_player_name().await.unwrap_or(String::new()) })
+    }))
+}
+
+#[gm_func]
+pub fn __crystal_set_variable_async(name: &str, variable: &str) -> f64 {
+    #[cfg(feature = "debug")]
+    println!("set_variable_async({name:?}, {variable:?})");
+    let variable = match decode_vari(variable) {
+        Ok(variable) => variable,
+        Err(err) => return rejected(err),
+    };
+    let name = name.to_owned();
+    ticket(
+        client().run(move |crystal| {
+            Box::pin(async move { crystal.set_variable(&name, variable).await })
+        }),
+    )
+}
+
+#[gm_func]
+pub fn __crystal_remove_variable_async(name: &str) -> f64 {
+    #[cfg(feature = "debug")]
+    println!("remove_variable_async({name:?})");
+    let name = name.to_owned();
+    ticket(
+        client().run(move |crystal| Box::pin(async move { crystal.remove_variable(&name).await })),
+    )
+}
+
+#[gm_func]
+pub fn __crystal_iter_other_players_async() -> f64 {
+    #[cfg(feature = "debug")]
+    println!("iter_other_players_async()");
+    ticket(client().run(move |crystal| {
+        Box::pin(async move {
+            let lock = crystal;
+            let iter = lock.iter_other_players().await;
+            pin_mut!(iter);
+            let mut res = Vec::new();
+            while let Some((pid, player)) = iter.next().await {
+                res.push(encode_player(pid, &player));
+            }
+            encode_list(res)
+        })
+    }))
+}
+
+#[gm_func]
+pub fn __crystal_other_player_count_async() -> f64 {
+    #[cfg(feature = "debug")]
+    println!("other_player_count_async()");
+    ticket(
+        client()
+            .run(move |crystal| Box::pin(async move { crystal.other_player_count().await as f64 })),
+    )
+}
+
+#[gm_func]
+pub fn __crystal_get_other_player_async(pid: f64) -> f64 {
+    #[cfg(feature = "debug")]
+    println!("get_other_player_async({pid:?})");
+    ticket(client().run(move |crystal| {
+        Box::pin(async move {
+            if let Some(player) = crystal.get_other_player(pid as u64).await {
+                encode_player(pid as u64, &player)
+            } else {
+                encode_none()
+            }
+        })
+    }))
+}
+
+#[gm_func]
+pub fn __crystal_get_other_player_name_async(name: &str) -> f64 {
+    #[cfg(feature = "debug")]
+    println!("get_other_player_name_async({name:?})");
+    let name = name.to_owned();
+    ticket(client().run(move |crystal| {
+        Box::pin(async move {
+            if let Some((pid, player)) = crystal.get_other_player_name(&name).await {
+                encode_player(pid, &player)
+            } else {
+                encode_none()
+            }
+        })
+    }))
+}
+
+#[gm_func]
+pub fn __crystal_request_other_player_variable_async(pid: f64, name: &str, request: f64) -> f64 {
+    #[cfg(feature = "debug")]
+    println!("request_other_player_variable_async({pid:?}, {name:?}, {request:?})");
+    let name = name.to_owned();
+    let notifier = notifications::notifier();
+    ticket(client().run(move |crystal| {
+        Box::pin(async move {
+            crystal
+                .request_other_player_variable(
+                    pid as u64,
+                    &name,
+                    Some(Box::new(move |pid, name, vari| {
+                        notifier.push(Notification::PlayerVariableRequest {
+                            request,
+                            pid,
+                            name: name.to_string(),
+                            value: vari,
+                        });
+                    })),
+                )
+                .await
+                .map(|_| ())
+        })
+    }))
+}
+
+#[gm_func]
+pub fn __crystal_p2p_async(target: f64, mid: f64, payload: &str) -> f64 {
+    #[cfg(feature = "debug")]
+    println!("p2p_async({target:?}, {mid:?}, {payload:?})");
+    let payload = match decode_payload(payload) {
+        Ok(payload) => payload,
+        Err(err) => return rejected(err),
+    };
+    ticket(client().run(move |crystal| {
+        Box::pin(async move {
+            crystal
+                .p2p(decode_player_request(target), mid as i16, payload)
+                .await
+        })
+    }))
+}
+
+#[gm_func]
+pub fn __crystal_set_version_async(version: f64) -> f64 {
+    #[cfg(feature = "debug")]
+    println!("set_version_async({version:?})");
+    instance::current()
+        .has_version
+        .store(true, Ordering::Relaxed);
+    ticket(client().run(move |crystal| Box::pin(async move { crystal.set_version(version).await })))
+}
+
+#[gm_func]
+pub fn __crystal_get_version_async() -> f64 {
+    #[cfg(feature = "debug")]
+    println!("get_version_async()");
+    ticket(client().run(move |crystal| Box::pin(async move { crystal.get_version().await })))
+}
+
+#[gm_func]
+pub fn __crystal_get_server_version_async() -> f64 {
+    #[cfg(feature = "debug")]
+    println!("get_server_version_async()");
+    ticket(client().run(move |crystal| Box::pin(async move { crystal.get_server_version().await })))
+}
+
+#[gm_func]
+pub fn __crystal_set_session_async(session: &str) -> f64 {
+    #[cfg(feature = "debug")]
+    println!("set_session_async({session:?})");
+    let session = session.to_owned();
+    ticket(
+        client().run(move |crystal| Box::pin(async move { crystal.set_session(&session).await })),
+    )
+}
+
+#[gm_func]
+pub fn __crystal_get_session_async() -> f64 {
+    #[cfg(feature = "debug")]
+    println!("get_session_async()");
+    ticket(client().run(move |crystal| Box::pin(async move { crystal.get_session().await })))
+}
+
+#[gm_func]
+pub fn __crystal_get_open_playerini_async() -> f64 {
+    #[cfg(feature = "debug")]
+    println!("get_open_playerini_async()");
+    ticket(client().run(move |crystal| Box::pin(async move { crystal.get_open_playerini().await })))
+}
+
+#[gm_func]
+pub fn __crystal_open_playerini_async(file: &str) -> f64 {
+    #[cfg(feature = "debug")]
+    println!("open_playerini_async({file:?})");
+    let file = file.to_owned();
+    ticket(
+        client().run(move |crystal| Box::pin(async move { crystal.open_playerini(&file).await })),
+    )
+}
+
+#[gm_func]
+pub fn __crystal_close_playerini_async() -> f64 {
+    #[cfg(feature = "debug")]
+    println!("close_playerini_async()");
+    ticket(client().run(move |crystal| Box::pin(async move { crystal.close_playerini().await })))
+}
+
+#[gm_func]
+pub fn __crystal_has_playerini_async(section: &str, key: &str) -> f64 {
+    #[cfg(feature = "debug")]
+    println!("has_playerini_async({section:?}, {key:?})");
+    let (section, key) = (section.to_owned(), key.to_owned());
+    ticket(
+        client().run(move |crystal| {
+            Box::pin(async move { crystal.has_playerini(&section, &key).await })
+        }),
+    )
+}
+
+#[gm_func]
+pub fn __crystal_get_playerini_async(section: &str, key: &str) -> f64 {
+    #[cfg(feature = "debug")]
+    println!("get_playerini_async({section:?}, {key:?})");
+    let (section, key) = (section.to_owned(), key.to_owned());
+    ticket(client().run(move |crystal| {
+        Box::pin(async move {
+            if let Some(vari) = crystal.get_playerini(&section, &key).await {
+                encode_vari(&vari)
+            } else {
+                String::from("!")
+            }
+        })
+    }))
+}
+
+#[gm_func]
+pub fn __crystal_set_playerini_async(section: &str, key: &str, vari: &str) -> f64 {
+    #[cfg(feature = "debug")]
+    println!("set_playerini_async({section:?}, {key:?}, {vari:?})");
+    let vari = match decode_vari(vari) {
+        Ok(vari) => vari,
+        Err(err) => return rejected(err),
+    };
+    let (section, key) = (section.to_owned(), key.to_owned());
+    ticket(client().run(move |crystal| {
+        Box::pin(async move { crystal.set_playerini(&section, &key, vari).await })
+    }))
+}
+
+#[gm_func]
+pub fn __crystal_remove_playerini_async(section: &str, key: &str) -> f64 {
+    #[cfg(feature = "debug")]
+    println!("remove_playerini_async({section:?}, {key:?})");
+    let (section, key) = (section.to_owned(), key.to_owned());
+    ticket(client().run(move |crystal| {
+        Box::pin(async move { crystal.remove_playerini(&section, &key).await })
+    }))
+}
+
+#[gm_func]
+pub fn __crystal_get_open_gameini_async() -> f64 {
+    #[cfg(feature = "debug")]
+    println!("get_open_gameini_async()");
+    ticket(client().run(move |crystal| Box::pin(async move { crystal.get_open_gameini().await })))
+}
+
+#[gm_func]
+pub fn __crystal_open_gameini_async(file: &str) -> f64 {
+    #[cfg(feature = "debug")]
+    println!("open_gameini_async({file:?})");
+    let file = file.to_owned();
+    ticket(client().run(move |crystal| Box::pin(async move { crystal.open_gameini(&file).await })))
+}
+
+#[gm_func]
+pub fn __crystal_close_gameini_async() -> f64 {
+    #[cfg(feature = "debug")]
+    println!("close_gameini_async()");
+    ticket(client().run(move |crystal| Box::pin(async move { crystal.close_gameini().await })))
+}
+
+#[gm_func]
+pub fn __crystal_has_gameini_async(section: &str, key: &str) -> f64 {
+    #[cfg(feature = "debug")]
+    println!("has_gameini_async({section:?}, {key:?})");
+    let (section, key) = (section.to_owned(), key.to_owned());
+    ticket(
+        client()
+            .run(move |crystal| Box::pin(async move { crystal.has_gameini(&section, &key).await })),
+    )
+}
+
+#[gm_func]
+pub fn __crystal_get_gameini_async(section: &str, key: &str) -> f64 {
+    #[cfg(feature = "debug")]
+    println!("get_gameini_async({section:?}, {key:?})");
+    let (section, key) = (section.to_owned(), key.to_owned());
+    ticket(client().run(move |crystal| {
+        Box::pin(async move {
+            if let Some(vari) = crystal.get_gameini(&section, &key).await {
+                encode_vari(&vari)
+            } else {
+                String::from("!")
+            }
+        })
+    }))
+}
+
+#[gm_func]
+pub fn __crystal_set_gameini_async(section: &str, key: &str, vari: &str) -> f64 {
+    #[cfg(feature = "debug")]
+    println!("set_gameini_async({section:?}, {key:?}, {vari:?})");
+    let vari = match decode_vari(vari) {
+        Ok(vari) => vari,
+        Err(err) => return rejected(err),
+    };
+    let (section, key) = (section.to_owned(), key.to_owned());
+    ticket(client().run(move |crystal| {
+        Box::pin(async move { crystal.set_gameini(&section, &key, vari).await })
+    }))
+}
+
+#[gm_func]
+pub fn __crystal_remove_gameini_async(section: &str, key: &str) -> f64 {
+    #[cfg(feature = "debug")]
+    println!("remove_gameini_async({section:?}, {key:?})");
+    let (section, key) = (section.to_owned(), key.to_owned());
+    ticket(
+        client().run(move |crystal| {
+            Box::pin(async move { crystal.remove_gameini(&section, &key).await })
+        }),
+    )
+}
+
+#[gm_func]
+pub fn __crystal_has_achievement_async(aid: f64) -> f64 {
+    #[cfg(feature = "debug")]
+    println!("has_achievement_async({aid:?})");
+    ticket(
+        client()
+            .run(move |crystal| Box::pin(async move { crystal.has_achievement(aid as u64).await })),
+    )
+}
+
+#[gm_func]
+pub fn __crystal_get_achievement_async(aid: f64) -> f64 {
+    #[cfg(feature = "debug")]
+    println!("get_achievement_async({aid:?})");
+    let reached = instance::current().reached.clone();
+    ticket(client().run(move |crystal| {
+        Box::pin(async move {
+            encode_achievement(&get_achievement(crystal, &reached, aid as u64).await)
+        })
+    }))
+}
+
+#[gm_func]
+pub fn __crystal_has_reached_achievement_async(aid: f64) -> f64 {
+    #[cfg(feature = "debug")]
+    println!("has_reached_achievement_async({aid:?})");
+    ticket(client().run(move |crystal| {
+        Box::pin(async move { crystal.has_reached_achievement(aid as u64).await })
+    }))
+}
+
+#[gm_func]
+pub fn __crystal_get_reached_achievement_async(aid: f64) -> f64 {
+    #[cfg(feature = "debug")]
+    println!("get_reached_achievement_async({aid:?})");
+    let reached = instance::current().reached.clone();
+    ticket(client().run(move |crystal| {
+        Box::pin(async move {
+            get_reached_achievement(crystal, &reached, aid as u64)
+                .await
+                .map(|val| val as f64)
+                .unwrap_or(f64::NAN)
+        })
+    }))
+}
+
+#[gm_func]
+pub fn __crystal_reach_achievement_async(aid: f64) -> f64 {
+    #[cfg(feature = "debug")]
+    println!("reach_achievement_async({aid:?})");
+    let reached = instance::current().reached.clone();
+    ticket(client().run(move |crystal| {
+        Box::pin(async move { reach_achievement(crystal, &reached, aid as u64).await })
+    }))
+}
+
+#[gm_func]
+pub fn __crystal_has_highscore_async(hid: f64) -> f64 {
+    #[cfg(feature = "debug")]
+    println!("has_highscore_async({hid:?})");
+    ticket(
+        client()
+            .run(move |crystal| Box::pin(async move { crystal.has_highscore(hid as u64).await })),
+    )
+}
+
+#[gm_func]
+pub fn __crystal_get_highscore_async(hid: f64) -> f64 {
+    #[cfg(feature = "debug")]
+    println!("get_highscore_async({hid:?})");
+    ticket(client().run(move |crystal| {
+        Box::pin(async move { encode_highscore(&crystal.get_highscore(hid as u64).await) })
+    }))
+}
+
+#[gm_func]
+pub fn __crystal_has_score_highscore_async(hid: f64) -> f64 {
+    #[cfg(feature = "debug")]
+    println!("has_score_highscore_async({hid:?})");
+    ticket(
+        client().run(move |crystal| {
+            Box::pin(async move { crystal.has_score_highscore(hid as u64).await })
+        }),
+    )
+}
+
+#[gm_func]
+pub fn __crystal_get_score_highscore_async(hid: f64) -> f64 {
+    #[cfg(feature = "debug")]
+    println!("get_score_highscore_async({hid:?})");
+    ticket(client().run(move |crystal| {
+        Box::pin(async move {
+            crystal
+                .get_score_highscore(hid as u64)
+                .await
+                .unwrap_or(f64::NAN)
+        })
+    }))
+}
+
+#[gm_func]
+pub fn __crystal_set_score_highscore_async(hid: f64, score: f64) -> f64 {
+    #[cfg(feature = "debug")]
+    println!("set_score_highscore_async({hid:?}, {score:?})");
+    ticket(client().run(move |crystal| {
+        Box::pin(async move { crystal.set_score_highscore(hid as u64, score).await })
+    }))
+}
+
+#[gm_func]
+pub fn __crystal_create_sync_async(sync_type: f64, kind: f64) -> f64 {
+    #[cfg(feature = "debug")]
+    println!("create_sync_async({sync_type:?}, {kind:?})");
+    ticket(client().run(move |crystal| {
+        Box::pin(async move {
+            let sync_type = SyncType::try_from(sync_type as u8).map_err(|e| e.to_string())?;
+            Ok::<_, String>(crystal.create_sync(sync_type, kind as i16).await as f64)
+        })
+    }))
+}
+
+#[gm_func]
+pub fn __crystal_destroy_sync_async(sync: f64) -> f64 {
+    #[cfg(feature = "debug")]
+    println!("destroy_sync_async({sync:?})");
+    ticket(
+        client()
+            .run(move |crystal| Box::pin(async move { crystal.destroy_sync(sync as usize).await })),
+    )
+}
+
+#[gm_func]
+pub fn __crystal_set_variable_sync_async(sync: f64, name: &str, value: &str) -> f64 {
+    #[cfg(feature = "debug")]
+    println!("set_variable_sync_async({sync:?}, {name:?}, {value:?})");
+    let value = match decode_vari(value) {
+        Ok(value) => value,
+        Err(err) => return rejected(err),
+    };
+    let name = name.to_owned();
+    ticket(client().run(move |crystal| {
+        Box::pin(async move { crystal.set_variable_sync(sync as usize, &name, value).await })
+    }))
+}
+
+#[gm_func]
+pub fn __crystal_remove_variable_sync_async(sync: f64, name: &str) -> f64 {
+    #[cfg(feature = "debug")]
+    println!("remove_variable_sync_async({sync:?}, {name:?})");
+    let name = name.to_owned();
+    ticket(client().run(move |crystal| {
+        Box::pin(async move { crystal.remove_variable_sync(sync as usize, &name).await })
+    }))
+}
+
+#[gm_func]
+pub fn __crystal_get_variable_other_sync_async(pid: f64, sync: f64, name: &str) -> f64 {
+    #[cfg(feature = "debug")]
+    println!("get_variable_other_sync_async({pid:?}, {sync:?}, {name:?})");
+    let name = name.to_owned();
+    ticket(client().run(move |crystal| {
+        Box::pin(async move {
+            if let Some(vari) = crystal
+                .get_variable_other_sync(pid as u64, sync as usize, &name)
+                .await
+            {
+                encode_vari(&vari)
+            } else {
+                String::from("!")
+            }
+        })
+    }))
+}
+
+#[gm_func]
+pub fn __crystal_iter_other_syncs_async() -> f64 {
+    #[cfg(feature = "debug")]
+    println!("iter_other_syncs_async()");
+    ticket(client().run(move |crystal| {
+        Box::pin(async move {
+            let lock = crystal;
+            let iter = lock.iter_other_syncs().await;
+            pin_mut!(iter);
+            let mut res = Vec::new();
+            while let Some(sync) = iter.next().await {
+                res.push(encode_synciter(&sync));
+            }
+            encode_list(res)
+        })
+    }))
+}
+
+#[gm_func]
+pub fn __crystal_is_player_admin_async(pid: f64) -> f64 {
+    #[cfg(feature = "debug")]
+    println!("is_player_admin_async({pid:?})");
+    ticket(
+        client()
+            .run(move |crystal| Box::pin(async move { crystal.is_player_admin(pid as u64).await })),
+    )
+}
+
+#[gm_func]
+pub fn __crystal_get_player_admin_async(pid: f64) -> f64 {
+    #[cfg(feature = "debug")]
+    println!("get_player_admin_async({pid:?})");
+    ticket(client().run(move |crystal| {
+        Box::pin(async move {
+            if let Some(admin) = crystal.get_player_admin(pid as u64).await {
+                encode_administrator(&admin)
+            } else {
+                encode_none()
+            }
+        })
+    }))
+}
+
+#[gm_func]
+pub fn __crystal_player_kick_async(pid: f64, reason: &str) -> f64 {
+    #[cfg(feature = "debug")]
+    println!("player_kick_async({pid:?}, {reason:?})");
+    let reason = reason.to_owned();
+    ticket(client().run(move |crystal| {
+        Box::pin(async move { crystal.player_kick(pid as u64, &reason).await })
+    }))
+}
+
+#[gm_func]
+pub fn __crystal_player_ban_async(pid: f64, reason: &str, unban_time: f64) -> f64 {
+    #[cfg(feature = "debug")]
+    println!("player_ban_async({pid:?}, {reason:?}, {unban_time:?})");
+    let reason = reason.to_owned();
+    ticket(client().run(move |crystal| {
+        Box::pin(async move {
+            let unban_time = DateTime::from_timestamp(unban_time as i64, 0)
+                .ok_or_else(|| format!("invalid unban time {unban_time}"))?;
+            crystal
+                .player_ban(pid as u64, &reason, unban_time)
+                .await
+                .map_err(|e| e.to_string())
+        })
+    }))
+}
+
+#[gm_func]
+pub fn __crystal_player_unban_async(pid: f64) -> f64 {
+    #[cfg(feature = "debug")]
+    println!("player_unban_async({pid:?})");
+    ticket(
+        client()
+            .run(move |crystal| Box::pin(async move { crystal.player_unban(pid as u64).await })),
+    )
+}
+
+#[gm_func]
+pub fn __crystal_logout_async() -> f64 {
+    #[cfg(feature = "debug")]
+    println!("logout_async()");
+    instance::current().reconnect.forget_login();
+    ticket(client().run(move |crystal| Box::pin(async move { crystal.logout().await })))
+}
+
+#[gm_func]
+pub fn __crystal_request_other_sync_variable_async(
+    pid: f64,
+    slot: f64,
+    name: &str,
+    request: f64,
+) -> f64 {
+    #[cfg(feature = "debug")]
+    println!("request_other_sync_variable_async({pid:?}, {slot:?}, {name:?}, {request:?})");
+    let name = name.to_owned();
+    let notifier = notifications::notifier();
+    ticket(client().run(move |crystal| {
+        Box::pin(async move {
+            crystal
+                .request_other_sync_variable(
+                    pid as u64,
+                    slot as usize,
+                    &name,
+                    Some(Box::new(move |pid, name, vari| {
+                        notifier.push(Notification::SyncVariableRequest {
+                            request,
+                            pid,
+                            name: name.to_string(),
+                            value: vari,
+                        });
+                    })),
+                )
+                .await
+                .map(|_| ())
+        })
+    }))
+}
+
+#[gm_func]
+pub fn __crystal_fetch_bdb_async(name: &str) -> f64 {
+    #[cfg(feature = "debug")]
+    println!("fetch_bdb_async({name:?})");
+    let name = name.to_owned();
+    ticket(client().run(move |crystal| {
+        Box::pin(async move { crystal.fetch_bdb(&name, None).await.map(|_| ()) })
+    }))
+}
+
+#[gm_func]
+pub fn __crystal_set_bdb_async(name: &str, data: &str) -> f64 {
+    #[cfg(feature = "debug")]
+    println!("set_bdb_async({name:?}, {data:?})");
+    let (name, data) = (name.to_owned(), BASE64_STANDARD.decode(data));
+    ticket(client().run(move |crystal| {
+        Box::pin(async move {
+            let data = data.map_err(|e| e.to_string())?;
+            crystal
+                .set_bdb(&name, data)
+                .await
+                .map_err(|e| e.to_string())
+        })
+    }))
+}
+
+#[gm_func]
+pub fn __crystal_get_incoming_friends_async() -> f64 {
+    #[cfg(feature = "debug")]
+    println!("get_incoming_friends_async()");
+    ticket(client().run(move |crystal| {
+        Box::pin(async move {
+            encode_friends(
+                crystal
+                    .get_incoming_friends()
+                    .await
+                    .unwrap_or_default()
+                    .into_iter(),
+            )
+        })
+    }))
+}
+
+#[gm_func]
+pub fn __crystal_get_outgoing_friends_async() -> f64 {
+    #[cfg(feature = "debug")]
+    println!("get_outgoing_friends_async()");
+    ticket(client().run(move |crystal| {
+        Box::pin(async move {
+            encode_friends(
+                crystal
+                    .get_outgoing_friends()
+                    .await
+                    .unwrap_or_default()
+                    .into_iter(),
+            )
+        })
+    }))
+}
+
+#[gm_func]
+pub fn __crystal_get_friends_async() -> f64 {
+    #[cfg(feature = "debug")]
+    println!("get_friends_async()");
+    ticket(client().run(move |crystal| {
+        Box::pin(async move {
+            encode_friends(crystal.get_friends().await.unwrap_or_default().into_iter())
+        })
+    }))
+}
+
+#[gm_func]
+pub fn __crystal_send_outgoing_friend_async(pid: f64) -> f64 {
+    #[cfg(feature = "debug")]
+    println!("send_outgoing_friend_async({pid:?})");
+    ticket(client().run(move |crystal| {
+        Box::pin(async move { crystal.send_outgoing_friend(pid as u64).await })
+    }))
+}
+
+#[gm_func]
+pub fn __crystal_remove_outgoing_friend_async(pid: f64) -> f64 {
+    #[cfg(feature = "debug")]
+    println!("remove_outgoing_friend_async({pid:?})");
+    ticket(client().run(move |crystal| {
+        Box::pin(async move { crystal.remove_outgoing_friend(pid as u64).await })
+    }))
+}
+
+#[gm_func]
+pub fn __crystal_deny_incoming_friend_async(pid: f64) -> f64 {
+    #[cfg(feature = "debug")]
+    println!("deny_incoming_friend_async({pid:?})");
+    ticket(client().run(move |crystal| {
+        Box::pin(async move { crystal.deny_incoming_friend(pid as u64).await })
+    }))
+}
+
+#[gm_func]
+pub fn __crystal_accept_incoming_friend_async(pid: f64) -> f64 {
+    #[cfg(feature = "debug")]
+    println!("accept_incoming_friend_async({pid:?})");
+    ticket(client().run(move |crystal| {
+        Box::pin(async move { crystal.accept_incoming_friend(pid as u64).await })
+    }))
+}
+
+#[gm_func]
+pub fn __crystal_remove_friend_async(pid: f64) -> f64 {
+    #[cfg(feature = "debug")]
+    println!("remove_friend_async({pid:?})");
+    ticket(
+        client()
+            .run(move |crystal| Box::pin(async move { crystal.remove_friend(pid as u64).await })),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tokio::sync::oneshot;
+
+    use super::*;
+
+    #[test]
+    fn ticket_ids_increase() {
+        instance::scratch(|| {
+            let ids: Vec<_> = (0..3).map(|_| ticket(async {})).collect();
+            assert!(ids.is_sorted_by(|a, b| a < b), "{ids:?}");
+        });
+    }
+
+    #[test]
+    fn failed_calls_are_reported() {
+        assert_eq!(
+            outcome::<bool>(Err(TimedOut)),
+            Err(String::from("the call timed out"))
+        );
+        assert_eq!(outcome(Ok(Ok(true))), Ok(String::from("1")));
+        instance::scratch(|| {
+            let id = ticket::<()>(async { panic!("ticket") });
+            RUNTIME.get().block_on(drained());
+            let panicked = BASE64_STANDARD.encode("the call panicked");
+            assert_eq!(
+                notifications::__crystal_get_notifications(),
+                format!("1;ticket;{id};0;{panicked}")
+            );
+        });
+    }
+
+    #[test]
+    fn running_tickets_hold_off_drained() {
+        instance::scratch(|| {
+            let (release, released) = oneshot::channel::<()>();
+            let id = ticket(async move {
+                let _ = released.await;
+            });
+            let runtime = RUNTIME.get();
+            assert!(IN_FLIGHT.load(Ordering::Acquire) >= 1);
+            let waited = async { tokio::time::timeout(Duration::from_millis(50), drained()).await };
+            assert!(runtime.block_on(waited).is_err());
+
+            release.send(()).unwrap();
+            runtime.block_on(drained());
+            assert_eq!(
+                notifications::__crystal_get_notifications(),
+                format!("1;ticket;{id};1;")
+            );
+        });
+    }
+}
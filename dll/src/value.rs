@@ -0,0 +1,857 @@
+//! Text encoding of [`Value`]s exchanged with GML.
+//!
+//! # Version 1
+//!
+//! A value is a type tag followed by its `:`-separated fields: `!` for `null`, `0:{int}`,
+//! `1:{float}`, `2:{0|1}`, `3:{base64 string}`, `4:{base64 buffer}`, `5:{len}` followed by the
+//! base64 of each encoded element and `6:{len}` followed by the base64 of each member's name
+//! and encoded value. A payload is its length followed by the encoded values, separated by `;`.
+//!
+//! # Version 2
+//!
+//! Starts with the version tag `v2:`, followed by a single value which nests its elements as is,
+//! so it's decoded in one pass and doesn't grow with the depth:
+//!
+//! | token               | value                                              |
+//! |---------------------|----------------------------------------------------|
+//! | `n`                 | `null`                                             |
+//! | `i{int};`           | integer                                            |
+//! | `f{float};`         | float                                              |
+//! | `I{16 hex digits}`  | integer, its two's complement bits                 |
+//! | `F{16 hex digits}`  | float, its IEEE 754 bits                           |
+//! | `b{0\|1}`           | bool                                               |
+//! | `s{len}:{string}`   | string, `len` is its byte length                   |
+//! | `S{len}:{base64}`   | string containing a NUL byte, which GML can't hold |
+//! | `x{len}:{base64}`   | buffer, `len` is the length of the base64          |
+//! | `a{len}:`           | array, followed by its `len` elements              |
+//! | `o{len}:`           | struct, followed by the name (`s` or `S`) and value of each member |
+//!
+//! A payload is encoded as an array.
+//!
+//! `i` and `f` are used by default. Their text may go through GML reals, which can't hold every
+//! `i64` and don't keep every float (e.g. the payload of NaN), so the game may ask for `I` and `F`
+//! with `__crystal_set_lossless_values` to get back exactly what was sent.
+//!
+//! Struct members come in no particular order, unless the game asks for them sorted by name
+//! (byte-wise) with `__crystal_set_canonical_values`, so equal values are encoded the same.
+//!
+//! A JSON object or array, as written by `json_stringify`, is decoded as described in
+//! [`crate::json`], and a payload may be a JSON array.
+//!
+//! Decoding accepts both versions, the one used for encoding is picked by the game with
+//! `__crystal_set_value_format` and defaults to version 1 for games which don't know of version 2.
+
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    sync::atomic::{AtomicBool, AtomicU8, Ordering},
+};
+
+use base64::{Engine, prelude::BASE64_STANDARD};
+use crystal_server::types::Value;
+use gm_utils::gm_func;
+
+use crate::json;
+
+static LAST_DECODE_ERROR: parking_lot::Mutex<Option<DecodeError>> = parking_lot::Mutex::new(None);
+static FORMAT: AtomicU8 = AtomicU8::new(1);
+static LOSSLESS: AtomicBool = AtomicBool::new(false);
+static CANONICAL: AtomicBool = AtomicBool::new(false);
+
+/// How deep arrays and structs may nest in version 2 before decoding gives up.
+const MAX_DEPTH: usize = 128;
+
+/// Why a value couldn't be decoded.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct DecodeError {
+    /// Byte offset of the offending field in the decoded string.
+    pub position: usize,
+    pub reason: String,
+    /// The error inside a nested, base64-encoded value.
+    pub inner: Option<Box<DecodeError>>,
+}
+
+impl DecodeError {
+    pub(crate) fn new(position: usize, reason: impl Into<String>) -> Self {
+        Self {
+            position,
+            reason: reason.into(),
+            inner: None,
+        }
+    }
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at byte {}", self.reason, self.position)?;
+        if let Some(inner) = &self.inner {
+            write!(f, ": {inner}")?;
+        }
+        Ok(())
+    }
+}
+
+/// The `separator`-separated fields of an encoded string, along with their offsets.
+struct Fields<'a> {
+    s: &'a str,
+    separator: char,
+    position: Option<usize>,
+}
+
+impl<'a> Fields<'a> {
+    fn new(s: &'a str, separator: char) -> Self {
+        Self {
+            s,
+            separator,
+            position: Some(0),
+        }
+    }
+
+    fn next(&mut self, what: &str) -> Result<(usize, &'a str), DecodeError> {
+        let Some(start) = self.position else {
+            return Err(DecodeError::new(self.s.len(), format!("missing {what}")));
+        };
+        let rest = &self.s[start..];
+        match rest.find(self.separator) {
+            Some(end) => {
+                self.position = Some(start + end + 1);
+                Ok((start, &rest[..end]))
+            }
+            None => {
+                self.position = None;
+                Ok((start, rest))
+            }
+        }
+    }
+
+    fn parse<T: std::str::FromStr>(&mut self, what: &str) -> Result<T, DecodeError> {
+        let (position, field) = self.next(what)?;
+        field
+            .parse()
+            .map_err(|_| DecodeError::new(position, format!("invalid {what} {field:?}")))
+    }
+
+    fn base64(&mut self, what: &str) -> Result<(usize, Vec<u8>), DecodeError> {
+        let (position, field) = self.next(what)?;
+        BASE64_STANDARD
+            .decode(field)
+            .map(|bytes| (position, bytes))
+            .map_err(|err| DecodeError::new(position, format!("invalid base64 in {what} ({err})")))
+    }
+
+    fn nested(&mut self, what: &str) -> Result<Value, DecodeError> {
+        let (position, bytes) = self.base64(what)?;
+        decode_v1(&String::from_utf8_lossy(&bytes)).map_err(|inner| DecodeError {
+            inner: Some(Box::new(inner)),
+            ..DecodeError::new(position, format!("invalid {what}"))
+        })
+    }
+
+    fn finish(self) -> Result<(), DecodeError> {
+        match self.position {
+            Some(position) => Err(DecodeError::new(position, "unexpected trailing data")),
+            None => Ok(()),
+        }
+    }
+}
+
+/// The input of a version 2 value, decoded from the front.
+struct Reader<'a> {
+    s: &'a str,
+    position: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn byte(&mut self, what: &str) -> Result<(usize, u8), DecodeError> {
+        let position = self.position;
+        match self.s.as_bytes().get(position) {
+            Some(&byte) => {
+                self.position += 1;
+                Ok((position, byte))
+            }
+            None => Err(DecodeError::new(position, format!("missing {what}"))),
+        }
+    }
+
+    /// Parses the text up to `terminator`, which is skipped.
+    fn parse<T: std::str::FromStr>(
+        &mut self,
+        what: &str,
+        terminator: u8,
+    ) -> Result<T, DecodeError> {
+        let position = self.position;
+        let Some(len) = self.s.as_bytes()[position..]
+            .iter()
+            .position(|&byte| byte == terminator)
+        else {
+            return Err(DecodeError::new(
+                self.s.len(),
+                format!("unterminated {what}"),
+            ));
+        };
+        let field = &self.s[position..position + len];
+        self.position += len + 1;
+        field
+            .parse()
+            .map_err(|_| DecodeError::new(position, format!("invalid {what} {field:?}")))
+    }
+
+    /// Reads a `{len}:` prefixed field.
+    fn field(&mut self, what: &str) -> Result<(usize, &'a str), DecodeError> {
+        let len = self.parse::<usize>(&format!("length of {what}"), b':')?;
+        let position = self.position;
+        let field = position
+            .checked_add(len)
+            .filter(|&end| end <= self.s.len())
+            .ok_or_else(|| DecodeError::new(position, format!("truncated {what}")))
+            .and_then(|end| {
+                self.s
+                    .get(position..end)
+                    .ok_or_else(|| DecodeError::new(position, format!("{what} splits a character")))
+            })?;
+        self.position += len;
+        Ok((position, field))
+    }
+
+    /// Reads 16 hex digits.
+    fn bits(&mut self, what: &str) -> Result<u64, DecodeError> {
+        let position = self.position;
+        let field = self
+            .s
+            .get(position..position + 16)
+            .ok_or_else(|| DecodeError::new(position, format!("truncated {what}")))?;
+        if !field.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+            return Err(DecodeError::new(
+                position,
+                format!("invalid {what} {field:?}"),
+            ));
+        }
+        self.position += 16;
+        Ok(u64::from_str_radix(field, 16).unwrap())
+    }
+
+    fn base64(&mut self, what: &str) -> Result<Vec<u8>, DecodeError> {
+        let (position, field) = self.field(what)?;
+        BASE64_STANDARD
+            .decode(field)
+            .map_err(|err| DecodeError::new(position, format!("invalid base64 in {what} ({err})")))
+    }
+
+    fn string(&mut self, what: &str) -> Result<String, DecodeError> {
+        match self.byte(what)? {
+            (_, b's') => Ok(self.field(what)?.1.to_owned()),
+            (_, b'S') => Ok(String::from_utf8_lossy(&self.base64(what)?).into_owned()),
+            (position, tag) => Err(DecodeError::new(
+                position,
+                format!("unknown {what} tag {:?}", tag as char),
+            )),
+        }
+    }
+
+    fn value(&mut self, depth: usize) -> Result<Value, DecodeError> {
+        let (position, tag) = self.byte("type tag")?;
+        if matches!(tag, b'a' | b'o') && depth == MAX_DEPTH {
+            return Err(DecodeError::new(position, "value nested too deep"));
+        }
+        Ok(match tag {
+            b'n' => Value::Null,
+            b'i' => Value::Int(self.parse("integer", b';')?),
+            b'f' => Value::Float(self.parse("float", b';')?),
+            b'I' => Value::Int(self.bits("integer")? as i64),
+            b'F' => Value::Float(f64::from_bits(self.bits("float")?)),
+            b'b' => match self.byte("bool")? {
+                (_, b'0') => Value::Bool(false),
+                (_, b'1') => Value::Bool(true),
+                (position, byte) => {
+                    return Err(DecodeError::new(
+                        position,
+                        format!("invalid bool {:?}", byte as char),
+                    ));
+                }
+            },
+            b's' => Value::String(self.field("string")?.1.to_owned()),
+            b'S' => Value::String(String::from_utf8_lossy(&self.base64("string")?).into_owned()),
+            b'x' => Value::Buffer(self.base64("buffer")?),
+            b'a' => {
+                let len = self.parse::<usize>("array length", b':')?;
+                let mut v = Vec::new();
+                for _ in 0..len {
+                    v.push(self.value(depth + 1)?);
+                }
+                Value::Array(v)
+            }
+            b'o' => {
+                let len = self.parse::<usize>("struct length", b':')?;
+                let mut v = HashMap::new();
+                for _ in 0..len {
+                    let name = self.string("member name")?;
+                    v.insert(name, self.value(depth + 1)?);
+                }
+                Value::Struct(v)
+            }
+            tag => {
+                return Err(DecodeError::new(
+                    position,
+                    format!("unknown type tag {:?}", tag as char),
+                ));
+            }
+        })
+    }
+
+    fn finish(self) -> Result<(), DecodeError> {
+        if self.position < self.s.len() {
+            Err(DecodeError::new(self.position, "unexpected trailing data"))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// The members of a struct or a variable map, sorted by name in canonical mode.
+pub(crate) fn members(map: &HashMap<String, Value>) -> Vec<(&String, &Value)> {
+    members_in(map, CANONICAL.load(Ordering::Relaxed))
+}
+
+/// The members of `map`, sorted by name if `canonical`.
+fn members_in(map: &HashMap<String, Value>, canonical: bool) -> Vec<(&String, &Value)> {
+    let mut members = map.iter().collect::<Vec<_>>();
+    if canonical {
+        members.sort_unstable_by_key(|(name, _)| *name);
+    }
+    members
+}
+
+/// Encodes a value in the format picked by the game.
+pub(crate) fn encode_vari(vari: &Value) -> String {
+    let canonical = CANONICAL.load(Ordering::Relaxed);
+    if FORMAT.load(Ordering::Relaxed) == 2 {
+        let mut s = String::from("v2:");
+        encode_v2(&mut s, vari, LOSSLESS.load(Ordering::Relaxed), canonical);
+        s
+    } else {
+        encode_v1(vari, canonical)
+    }
+}
+
+fn encode_v2_string(out: &mut String, s: &str) {
+    if s.contains('\0') {
+        let s = BASE64_STANDARD.encode(s);
+        out.push_str(&format!("S{}:{s}", s.len()));
+    } else {
+        out.push_str(&format!("s{}:{s}", s.len()));
+    }
+}
+
+fn encode_v2(out: &mut String, vari: &Value, lossless: bool, canonical: bool) {
+    match vari {
+        Value::Null => out.push('n'),
+        Value::Int(val) if lossless => out.push_str(&format!("I{val:016x}")),
+        Value::Int(val) => out.push_str(&format!("i{val};")),
+        Value::Float(val) if lossless => out.push_str(&format!("F{:016x}", val.to_bits())),
+        Value::Float(val) => out.push_str(&format!("f{val};")),
+        Value::Bool(val) => out.push_str(if *val { "b1" } else { "b0" }),
+        Value::String(val) => encode_v2_string(out, val),
+        Value::Buffer(val) => {
+            let val = BASE64_STANDARD.encode(val);
+            out.push_str(&format!("x{}:{val}", val.len()));
+        }
+        Value::Array(val) => {
+            out.push_str(&format!("a{}:", val.len()));
+            for val in val {
+                encode_v2(out, val, lossless, canonical);
+            }
+        }
+        Value::Struct(val) => {
+            out.push_str(&format!("o{}:", val.len()));
+            for (name, val) in members_in(val, canonical) {
+                encode_v2_string(out, name);
+                encode_v2(out, val, lossless, canonical);
+            }
+        }
+    }
+}
+
+fn encode_v1(vari: &Value, canonical: bool) -> String {
+    match vari {
+        Value::Null => String::from("!"),
+        Value::Int(val) => format!("0:{val}"),
+        Value::Float(val) => format!("1:{val}"),
+        Value::Bool(val) => format!("2:{}", *val as u8),
+        Value::String(val) => format!("3:{}", BASE64_STANDARD.encode(val)),
+        Value::Buffer(val) => format!("4:{}", BASE64_STANDARD.encode(val)),
+        Value::Array(val) => {
+            let mut s = format!("5:{}", val.len());
+            for val in val {
+                s.push_str(&format!(
+                    ":{}",
+                    BASE64_STANDARD.encode(encode_v1(val, canonical))
+                ));
+            }
+            s
+        }
+        Value::Struct(val) => {
+            let mut s = format!("6:{}", val.len());
+            for (name, val) in members_in(val, canonical) {
+                s.push_str(&format!(
+                    ":{}:{}",
+                    BASE64_STANDARD.encode(name),
+                    BASE64_STANDARD.encode(encode_v1(val, canonical))
+                ));
+            }
+            s
+        }
+    }
+}
+
+fn decode(s: &str) -> Result<Value, DecodeError> {
+    if s.starts_with(['{', '[']) {
+        return json::parse(s);
+    }
+    let Some(rest) = s.strip_prefix('v') else {
+        return decode_v1(s);
+    };
+    match rest.split_once(':') {
+        Some(("2", body)) => {
+            let mut reader = Reader {
+                s,
+                position: s.len() - body.len(),
+            };
+            let value = reader.value(0)?;
+            reader.finish()?;
+            Ok(value)
+        }
+        _ => Err(DecodeError::new(1, "unsupported format version")),
+    }
+}
+
+fn decode_v1(s: &str) -> Result<Value, DecodeError> {
+    let mut fields = Fields::new(s, ':');
+    let (position, tag) = fields.next("type tag")?;
+    let value = match tag {
+        "!" => Value::Null,
+        "0" => Value::Int(fields.parse("integer")?),
+        "1" => Value::Float(fields.parse("float")?),
+        "2" => Value::Bool(fields.parse::<i64>("bool")? != 0),
+        "3" => Value::String(String::from_utf8_lossy(&fields.base64("string")?.1).into_owned()),
+        "4" => Value::Buffer(fields.base64("buffer")?.1),
+        "5" => {
+            let len = fields.parse::<usize>("array length")?;
+            let mut v = Vec::new();
+            for i in 0..len {
+                v.push(fields.nested(&format!("element {i}"))?);
+            }
+            Value::Array(v)
+        }
+        "6" => {
+            let len = fields.parse::<usize>("struct length")?;
+            let mut v = HashMap::new();
+            for i in 0..len {
+                let name = fields.base64(&format!("name of member {i}"))?.1;
+                let val = fields.nested(&format!("value of member {i}"))?;
+                v.insert(String::from_utf8_lossy(&name).into_owned(), val);
+            }
+            Value::Struct(v)
+        }
+        tag => {
+            return Err(DecodeError::new(
+                position,
+                format!("unknown type tag {tag:?}"),
+            ));
+        }
+    };
+    fields.finish()?;
+    Ok(value)
+}
+
+pub(crate) fn report<T>(result: Result<T, DecodeError>) -> Result<T, DecodeError> {
+    if let Err(err) = &result {
+        *LAST_DECODE_ERROR.lock() = Some(err.clone());
+    }
+    result
+}
+
+/// Decodes a value sent by GML, failures are kept for `__crystal_get_last_decode_error`.
+pub(crate) fn decode_vari(s: &str) -> Result<Value, DecodeError> {
+    report(decode(s))
+}
+
+/// Decodes a payload sent by GML, failures are kept for `__crystal_get_last_decode_error`.
+pub(crate) fn decode_payload(payload: &str) -> Result<Vec<Value>, DecodeError> {
+    report((|| {
+        if payload.starts_with(['v', '[']) {
+            return match decode(payload)? {
+                Value::Array(data) => Ok(data),
+                _ => Err(DecodeError::new(0, "payload isn't an array")),
+            };
+        }
+        let mut fields = Fields::new(payload, ';');
+        let len = fields.parse::<usize>("payload length")?;
+        let mut data = Vec::new();
+        for i in 0..len {
+            let (position, field) = fields.next(&format!("payload value {i}"))?;
+            data.push(decode_v1(field).map_err(|inner| DecodeError {
+                inner: Some(Box::new(inner)),
+                ..DecodeError::new(position, format!("invalid payload value {i}"))
+            })?);
+        }
+        fields.finish()?;
+        Ok(data)
+    })())
+}
+
+/// Returns and clears the last error of a value sent by GML that couldn't be decoded.
+///
+/// The call which received that value was skipped, or its ticket failed with the same error.
+#[gm_func]
+pub fn __crystal_get_last_decode_error() -> String {
+    #[cfg(feature = "debug")]
+    println!("get_last_decode_error()");
+    LAST_DECODE_ERROR
+        .lock()
+        .take()
+        .map(|err| err.to_string())
+        .unwrap_or_default()
+}
+
+/// Picks the format of the values sent to GML, `1` or `2`. Returns whether it's supported.
+///
+/// Both are accepted from GML regardless of this.
+#[gm_func]
+pub fn __crystal_set_value_format(version: f64) -> bool {
+    #[cfg(feature = "debug")]
+    println!("set_value_format({version:?})");
+    if version == 1.0 || version == 2.0 {
+        FORMAT.store(version as u8, Ordering::Relaxed);
+        true
+    } else {
+        false
+    }
+}
+
+/// Toggles sending integers and floats as their bits in version 2, so they're decoded exactly.
+#[gm_func]
+pub fn __crystal_set_lossless_values(enabled: f64) {
+    #[cfg(feature = "debug")]
+    println!("set_lossless_values({enabled:?})");
+    LOSSLESS.store(enabled >= 0.5, Ordering::Relaxed);
+}
+
+/// Toggles sorting struct members and variables by name, so equal values always encode the same.
+#[gm_func]
+pub fn __crystal_set_canonical_values(enabled: f64) {
+    #[cfg(feature = "debug")]
+    println!("set_canonical_values({enabled:?})");
+    CANONICAL.store(enabled >= 0.5, Ordering::Relaxed);
+}
+
+/// Like `==`, but floats are compared by their bits, except for the payload of NaN when
+/// `lossless` is false.
+#[cfg(any(test, fuzzing))]
+fn equal(a: &Value, b: &Value, lossless: bool) -> bool {
+    match (a, b) {
+        (Value::Float(a), Value::Float(b)) => {
+            a.to_bits() == b.to_bits() || !lossless && a.is_nan() && b.is_nan()
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            a.len() == b.len() && a.iter().zip(b).all(|(a, b)| equal(a, b, lossless))
+        }
+        (Value::Struct(a), Value::Struct(b)) => {
+            a.len() == b.len()
+                && a.iter()
+                    .all(|(name, a)| b.get(name).is_some_and(|b| equal(a, b, lossless)))
+        }
+        (a, b) => a == b,
+    }
+}
+
+/// What the `decode_value` fuzz target (see `fuzz/`) checks for each input: decoding never
+/// panics, errors point inside the input, and whatever decodes survives a lossless round trip.
+#[cfg(fuzzing)]
+pub fn fuzz_decode(data: &[u8]) {
+    let Ok(s) = std::str::from_utf8(data) else {
+        return;
+    };
+    match decode(s) {
+        Ok(value) => {
+            let mut encoded = String::from("v2:");
+            encode_v2(&mut encoded, &value, true, false);
+            let decoded = decode(&encoded).unwrap_or_else(|err| panic!("{encoded:?}: {err}"));
+            assert!(
+                equal(&value, &decoded, true),
+                "{value:?} became {decoded:?}"
+            );
+        }
+        Err(err) => assert!(err.position <= s.len(), "{s:?}: {err}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{Rng, SeedableRng, rngs::StdRng};
+
+    use super::*;
+
+    fn same(a: &Value, b: &Value) -> bool {
+        equal(a, b, false)
+    }
+
+    fn random_string(rng: &mut StdRng) -> String {
+        const CHARS: &[char] = &['a', 'Z', '0', ':', ';', '!', '=', '/', '\0', 'é', '✓', '🦀'];
+        (0..rng.gen_range(0..8))
+            .map(|_| CHARS[rng.gen_range(0..CHARS.len())])
+            .collect()
+    }
+
+    fn random_value(rng: &mut StdRng, depth: u32) -> Value {
+        match rng.gen_range(0..if depth == 0 { 6 } else { 8 }) {
+            0 => Value::Null,
+            1 => Value::Int(match rng.gen_range(0..3) {
+                0 => rng.gen_range(-10..10),
+                1 => i64::MIN,
+                _ => rng.r#gen(),
+            }),
+            2 => Value::Float(match rng.gen_range(0..3) {
+                0 => rng.gen_range(-1e3..1e3),
+                1 => [
+                    0.0,
+                    -0.0,
+                    f64::INFINITY,
+                    f64::NEG_INFINITY,
+                    f64::NAN,
+                    f64::MIN_POSITIVE,
+                ][rng.gen_range(0..6)],
+                _ => f64::from_bits(rng.r#gen()),
+            }),
+            3 => Value::Bool(rng.r#gen()),
+            4 => Value::String(random_string(rng)),
+            5 => Value::Buffer((0..rng.gen_range(0..8)).map(|_| rng.r#gen()).collect()),
+            6 => Value::Array(
+                (0..rng.gen_range(0..4))
+                    .map(|_| random_value(rng, depth - 1))
+                    .collect(),
+            ),
+            _ => Value::Struct(
+                (0..rng.gen_range(0..4))
+                    .map(|_| (random_string(rng), random_value(rng, depth - 1)))
+                    .collect(),
+            ),
+        }
+    }
+
+    fn v2(value: &Value) -> String {
+        let mut s = String::from("v2:");
+        encode_v2(&mut s, value, false, false);
+        s
+    }
+
+    fn lossless(value: &Value) -> String {
+        let mut s = String::from("v2:");
+        encode_v2(&mut s, value, true, false);
+        s
+    }
+
+    #[test]
+    fn values_round_trip() {
+        let mut rng = StdRng::seed_from_u64(0x5EED);
+        for _ in 0..5000 {
+            let value = random_value(&mut rng, 3);
+            for encoded in [encode_v1(&value, false), v2(&value), lossless(&value)] {
+                let decoded = decode(&encoded).unwrap_or_else(|err| panic!("{encoded:?}: {err}"));
+                assert!(same(&value, &decoded), "{value:?} became {decoded:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn lossless_values_round_trip_exactly() {
+        let edges = [
+            Value::Int(i64::MIN),
+            Value::Int(i64::MAX),
+            Value::Int((1 << 53) + 1),
+            Value::Int(-1),
+            Value::Float(-0.0),
+            Value::Float(f64::NAN),
+            Value::Float(-f64::NAN),
+            Value::Float(f64::from_bits(0x7FF0_0000_0000_0001)),
+            Value::Float(f64::INFINITY),
+            Value::Float(f64::NEG_INFINITY),
+            Value::Float(f64::from_bits(1)),
+            Value::Float(f64::MAX),
+        ];
+        let mut rng = StdRng::seed_from_u64(0x1055);
+        let random = (0..5000).map(|_| random_value(&mut rng, 3));
+        for value in edges.into_iter().chain(random) {
+            let encoded = lossless(&value);
+            let decoded = decode(&encoded).unwrap_or_else(|err| panic!("{encoded:?}: {err}"));
+            assert!(
+                equal(&value, &decoded, true),
+                "{value:?} became {decoded:?}"
+            );
+        }
+        assert_eq!(lossless(&Value::Int(-2)), "v2:Ifffffffffffffffe");
+        assert_eq!(lossless(&Value::Float(-0.0)), "v2:F8000000000000000");
+    }
+
+    #[test]
+    fn canonical_values_sort_members() {
+        let names = (0..32).map(|i| format!("m{i}")).collect::<Vec<_>>();
+        let build = |names: &mut dyn Iterator<Item = &String>| {
+            Value::Struct(
+                names
+                    .map(|name| {
+                        let inner = HashMap::from([(name.clone(), Value::Int(1))]);
+                        (name.clone(), Value::Struct(inner))
+                    })
+                    .collect(),
+            )
+        };
+        let (a, b) = (build(&mut names.iter()), build(&mut names.iter().rev()));
+        let canonical_v2 = |value| {
+            let mut s = String::from("v2:");
+            encode_v2(&mut s, value, false, true);
+            s
+        };
+        let encoded = [
+            encode_v1(&a, true),
+            encode_v1(&b, true),
+            canonical_v2(&a),
+            canonical_v2(&b),
+        ];
+        assert_eq!(encoded[0], encoded[1]);
+        assert_eq!(encoded[2], encoded[3]);
+        let mut sorted = names.clone();
+        sorted.sort();
+        let expected = sorted
+            .iter()
+            .map(|name| format!("s{}:{name}o1:s{}:{name}i1;", name.len(), name.len()))
+            .collect::<String>();
+        assert_eq!(encoded[2], format!("v2:o32:{expected}"));
+    }
+
+    #[test]
+    fn v2_does_not_grow_with_depth() {
+        let mut value = Value::String(String::from("leaf"));
+        for _ in 0..64 {
+            value = Value::Array(vec![value]);
+        }
+        let encoded = v2(&value);
+        assert!(encoded.len() < 64 * 4 + 16, "{encoded}");
+        assert!(same(&decode(&encoded).unwrap(), &value));
+
+        let mut value = Value::Null;
+        for _ in 0..=MAX_DEPTH {
+            value = Value::Array(vec![value]);
+        }
+        assert_eq!(
+            decode(&v2(&value)).unwrap_err().to_string(),
+            format!("value nested too deep at byte {}", 3 + MAX_DEPTH * 3)
+        );
+    }
+
+    #[test]
+    fn payloads_round_trip() {
+        let mut rng = StdRng::seed_from_u64(0xBEEF);
+        for _ in 0..500 {
+            let values = (0..rng.gen_range(0..4))
+                .map(|_| random_value(&mut rng, 2))
+                .collect::<Vec<_>>();
+            let mut payload = values.len().to_string();
+            for value in &values {
+                payload.push(';');
+                payload.push_str(&encode_v1(value, false));
+            }
+            let values = Value::Array(values);
+            let decoded = decode_payload(&payload).unwrap();
+            assert!(same(&values, &Value::Array(decoded)));
+            let decoded = decode_payload(&v2(&values)).unwrap();
+            assert!(same(&values, &Value::Array(decoded)));
+        }
+    }
+
+    /// Mangles valid encodings and makes sure decoding never panics and points inside the input.
+    ///
+    /// A quick check only, the `decode_value` fuzz target digs deeper.
+    #[test]
+    fn malformed_values_are_rejected() {
+        const ALPHABET: &[u8] = b"!0123456789:;+/=AZaz-.enifbsSxaovIF";
+        let mut rng = StdRng::seed_from_u64(0xF022);
+        for i in 0..60000 {
+            let value = random_value(&mut rng, 2);
+            let mut bytes = match i % 3 {
+                0 => encode_v1(&value, false),
+                1 => v2(&value),
+                _ => lossless(&value),
+            }
+            .into_bytes();
+            for _ in 0..rng.gen_range(1..4) {
+                let at = rng.gen_range(0..=bytes.len());
+                match rng.gen_range(0..3) {
+                    0 if at < bytes.len() => bytes[at] = ALPHABET[rng.gen_range(0..ALPHABET.len())],
+                    1 if at < bytes.len() => {
+                        bytes.remove(at);
+                    }
+                    _ => bytes.insert(at, ALPHABET[rng.gen_range(0..ALPHABET.len())]),
+                }
+            }
+            let s = String::from_utf8_lossy(&bytes).into_owned();
+            if let Err(err) = decode(&s) {
+                assert!(err.position <= s.len(), "{s:?}: {err}");
+            }
+        }
+    }
+
+    #[test]
+    fn errors_point_at_the_field() {
+        let err = |s: &str| decode(s).unwrap_err();
+        assert_eq!(err("").to_string(), "unknown type tag \"\" at byte 0");
+        assert_eq!(
+            err("0:12a").to_string(),
+            "invalid integer \"12a\" at byte 2"
+        );
+        assert_eq!(err("0").to_string(), "missing integer at byte 1");
+        assert_eq!(
+            err("2:1:0").to_string(),
+            "unexpected trailing data at byte 4"
+        );
+        assert_eq!(err("3:%%").position, 2);
+        assert_eq!(err("5:2:MDox").to_string(), "missing element 1 at byte 8");
+        // "0:x" nested as the second element.
+        assert_eq!(
+            err("5:2:MDox:MDp4").to_string(),
+            "invalid element 1 at byte 9: invalid integer \"x\" at byte 2"
+        );
+        assert_eq!(
+            decode_payload("2;0:1").unwrap_err().to_string(),
+            "missing payload value 1 at byte 5"
+        );
+
+        assert_eq!(
+            err("v3:n").to_string(),
+            "unsupported format version at byte 1"
+        );
+        assert_eq!(err("v2:").to_string(), "missing type tag at byte 3");
+        assert_eq!(err("v2:i12").to_string(), "unterminated integer at byte 6");
+        assert_eq!(
+            err("v2:a2:nq").to_string(),
+            "unknown type tag 'q' at byte 7"
+        );
+        assert_eq!(err("v2:s5:abc").to_string(), "truncated string at byte 6");
+        assert_eq!(
+            err("v2:s1:é").to_string(),
+            "string splits a character at byte 6"
+        );
+        assert_eq!(
+            err("v2:nn").to_string(),
+            "unexpected trailing data at byte 4"
+        );
+        assert_eq!(err("v2:I00").to_string(), "truncated integer at byte 4");
+        assert_eq!(
+            err("v2:F+000000000000000").to_string(),
+            "invalid float \"+000000000000000\" at byte 4"
+        );
+        assert_eq!(
+            decode_payload("v2:n").unwrap_err().to_string(),
+            "payload isn't an array at byte 0"
+        );
+    }
+}
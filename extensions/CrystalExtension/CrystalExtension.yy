@@ -16,7 +16,7 @@
   "exportToGame":true,
   "extensionVersion":"1.0.0",
   "files":[
-    {"$GMExtensionFile":"","%Name":"","constants":[],"copyToTargets":194,"filename":"crystal_dll.dll","final":"","functions":[
+    {"$GMExtensionFile":"","%Name":"","constants":[],"copyToTargets":194,"filename":"crystal_dll.dll","final":"__crystal_shutdown","functions":[
         {"$GMExtensionFunction":"","%Name":"__crystal_set_room","argCount":0,"args":[1,],"documentation":"","externalName":"__crystal_set_room","help":"","hidden":false,"kind":1,"name":"__crystal_set_room","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
         {"$GMExtensionFunction":"","%Name":"__crystal_init","argCount":0,"args":[1,],"documentation":"","externalName":"__crystal_init","help":"","hidden":false,"kind":1,"name":"__crystal_init","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
         {"$GMExtensionFunction":"","%Name":"__crystal_connect","argCount":0,"args":[],"documentation":"","externalName":"__crystal_connect","help":"","hidden":false,"kind":1,"name":"__crystal_connect","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
@@ -93,6 +93,268 @@
         {"$GMExtensionFunction":"","%Name":"__crystal_deny_incoming_friend","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_deny_incoming_friend","help":"","hidden":false,"kind":1,"name":"__crystal_deny_incoming_friend","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
         {"$GMExtensionFunction":"","%Name":"__crystal_accept_incoming_friend","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_accept_incoming_friend","help":"","hidden":false,"kind":1,"name":"__crystal_accept_incoming_friend","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
         {"$GMExtensionFunction":"","%Name":"__crystal_remove_friend","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_remove_friend","help":"","hidden":false,"kind":1,"name":"__crystal_remove_friend","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_update_async","argCount":0,"args":[],"documentation":"","externalName":"__crystal_update_async","help":"","hidden":false,"kind":1,"name":"__crystal_update_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_is_connected_async","argCount":0,"args":[],"documentation":"","externalName":"__crystal_is_connected_async","help":"","hidden":false,"kind":1,"name":"__crystal_is_connected_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_is_connecting_async","argCount":0,"args":[],"documentation":"","externalName":"__crystal_is_connecting_async","help":"","hidden":false,"kind":1,"name":"__crystal_is_connecting_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_is_loggedin_async","argCount":0,"args":[],"documentation":"","externalName":"__crystal_is_loggedin_async","help":"","hidden":false,"kind":1,"name":"__crystal_is_loggedin_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_get_ping_async","argCount":0,"args":[],"documentation":"","externalName":"__crystal_get_ping_async","help":"","hidden":false,"kind":1,"name":"__crystal_get_ping_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_set_game_token_async","argCount":0,"args":[1,],"documentation":"","externalName":"__crystal_set_game_token_async","help":"","hidden":false,"kind":1,"name":"__crystal_set_game_token_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_disconnect_async","argCount":0,"args":[],"documentation":"","externalName":"__crystal_disconnect_async","help":"","hidden":false,"kind":1,"name":"__crystal_disconnect_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_login_async","argCount":0,"args":[1,1,],"documentation":"","externalName":"__crystal_login_async","help":"","hidden":false,"kind":1,"name":"__crystal_login_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_login_with_token_async","argCount":0,"args":[1,1,],"documentation":"","externalName":"__crystal_login_with_token_async","help":"","hidden":false,"kind":1,"name":"__crystal_login_with_token_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_register_async","argCount":0,"args":[1,1,1,1,],"documentation":"","externalName":"__crystal_register_async","help":"","hidden":false,"kind":1,"name":"__crystal_register_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_get_player_id_async","argCount":0,"args":[],"documentation":"","externalName":"__crystal_get_player_id_async","help":"","hidden":false,"kind":1,"name":"__crystal_get_player_id_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_get_player_name_async","argCount":0,"args":[],"documentation":"","externalName":"__crystal_get_player_name_async","help":"","hidden":false,"kind":1,"name":"__crystal_get_player_name_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_set_variable_async","argCount":0,"args":[1,1,],"documentation":"","externalName":"__crystal_set_variable_async","help":"","hidden":false,"kind":1,"name":"__crystal_set_variable_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_remove_variable_async","argCount":0,"args":[1,],"documentation":"","externalName":"__crystal_remove_variable_async","help":"","hidden":false,"kind":1,"name":"__crystal_remove_variable_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_iter_other_players_async","argCount":0,"args":[],"documentation":"","externalName":"__crystal_iter_other_players_async","help":"","hidden":false,"kind":1,"name":"__crystal_iter_other_players_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_other_player_count_async","argCount":0,"args":[],"documentation":"","externalName":"__crystal_other_player_count_async","help":"","hidden":false,"kind":1,"name":"__crystal_other_player_count_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_get_other_player_async","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_get_other_player_async","help":"","hidden":false,"kind":1,"name":"__crystal_get_other_player_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_get_other_player_name_async","argCount":0,"args":[1,],"documentation":"","externalName":"__crystal_get_other_player_name_async","help":"","hidden":false,"kind":1,"name":"__crystal_get_other_player_name_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_request_other_player_variable_async","argCount":0,"args":[2,1,2,],"documentation":"","externalName":"__crystal_request_other_player_variable_async","help":"","hidden":false,"kind":1,"name":"__crystal_request_other_player_variable_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_p2p_async","argCount":0,"args":[2,2,1,],"documentation":"","externalName":"__crystal_p2p_async","help":"","hidden":false,"kind":1,"name":"__crystal_p2p_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_set_version_async","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_set_version_async","help":"","hidden":false,"kind":1,"name":"__crystal_set_version_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_get_version_async","argCount":0,"args":[],"documentation":"","externalName":"__crystal_get_version_async","help":"","hidden":false,"kind":1,"name":"__crystal_get_version_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_get_server_version_async","argCount":0,"args":[],"documentation":"","externalName":"__crystal_get_server_version_async","help":"","hidden":false,"kind":1,"name":"__crystal_get_server_version_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_set_session_async","argCount":0,"args":[1,],"documentation":"","externalName":"__crystal_set_session_async","help":"","hidden":false,"kind":1,"name":"__crystal_set_session_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_get_session_async","argCount":0,"args":[],"documentation":"","externalName":"__crystal_get_session_async","help":"","hidden":false,"kind":1,"name":"__crystal_get_session_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_get_open_playerini_async","argCount":0,"args":[],"documentation":"","externalName":"__crystal_get_open_playerini_async","help":"","hidden":false,"kind":1,"name":"__crystal_get_open_playerini_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_open_playerini_async","argCount":0,"args":[1,],"documentation":"","externalName":"__crystal_open_playerini_async","help":"","hidden":false,"kind":1,"name":"__crystal_open_playerini_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_close_playerini_async","argCount":0,"args":[],"documentation":"","externalName":"__crystal_close_playerini_async","help":"","hidden":false,"kind":1,"name":"__crystal_close_playerini_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_has_playerini_async","argCount":0,"args":[1,1,],"documentation":"","externalName":"__crystal_has_playerini_async","help":"","hidden":false,"kind":1,"name":"__crystal_has_playerini_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_get_playerini_async","argCount":0,"args":[1,1,],"documentation":"","externalName":"__crystal_get_playerini_async","help":"","hidden":false,"kind":1,"name":"__crystal_get_playerini_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_set_playerini_async","argCount":0,"args":[1,1,1,],"documentation":"","externalName":"__crystal_set_playerini_async","help":"","hidden":false,"kind":1,"name":"__crystal_set_playerini_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_remove_playerini_async","argCount":0,"args":[1,1,],"documentation":"","externalName":"__crystal_remove_playerini_async","help":"","hidden":false,"kind":1,"name":"__crystal_remove_playerini_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_get_open_gameini_async","argCount":0,"args":[],"documentation":"","externalName":"__crystal_get_open_gameini_async","help":"","hidden":false,"kind":1,"name":"__crystal_get_open_gameini_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_open_gameini_async","argCount":0,"args":[1,],"documentation":"","externalName":"__crystal_open_gameini_async","help":"","hidden":false,"kind":1,"name":"__crystal_open_gameini_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_close_gameini_async","argCount":0,"args":[],"documentation":"","externalName":"__crystal_close_gameini_async","help":"","hidden":false,"kind":1,"name":"__crystal_close_gameini_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_has_gameini_async","argCount":0,"args":[1,1,],"documentation":"","externalName":"__crystal_has_gameini_async","help":"","hidden":false,"kind":1,"name":"__crystal_has_gameini_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_get_gameini_async","argCount":0,"args":[1,1,],"documentation":"","externalName":"__crystal_get_gameini_async","help":"","hidden":false,"kind":1,"name":"__crystal_get_gameini_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_set_gameini_async","argCount":0,"args":[1,1,1,],"documentation":"","externalName":"__crystal_set_gameini_async","help":"","hidden":false,"kind":1,"name":"__crystal_set_gameini_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_remove_gameini_async","argCount":0,"args":[1,1,],"documentation":"","externalName":"__crystal_remove_gameini_async","help":"","hidden":false,"kind":1,"name":"__crystal_remove_gameini_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_has_achievement_async","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_has_achievement_async","help":"","hidden":false,"kind":1,"name":"__crystal_has_achievement_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_get_achievement_async","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_get_achievement_async","help":"","hidden":false,"kind":1,"name":"__crystal_get_achievement_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_has_reached_achievement_async","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_has_reached_achievement_async","help":"","hidden":false,"kind":1,"name":"__crystal_has_reached_achievement_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_get_reached_achievement_async","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_get_reached_achievement_async","help":"","hidden":false,"kind":1,"name":"__crystal_get_reached_achievement_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_reach_achievement_async","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_reach_achievement_async","help":"","hidden":false,"kind":1,"name":"__crystal_reach_achievement_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_has_highscore_async","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_has_highscore_async","help":"","hidden":false,"kind":1,"name":"__crystal_has_highscore_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_get_highscore_async","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_get_highscore_async","help":"","hidden":false,"kind":1,"name":"__crystal_get_highscore_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_has_score_highscore_async","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_has_score_highscore_async","help":"","hidden":false,"kind":1,"name":"__crystal_has_score_highscore_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_get_score_highscore_async","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_get_score_highscore_async","help":"","hidden":false,"kind":1,"name":"__crystal_get_score_highscore_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_set_score_highscore_async","argCount":0,"args":[2,2,],"documentation":"","externalName":"__crystal_set_score_highscore_async","help":"","hidden":false,"kind":1,"name":"__crystal_set_score_highscore_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_create_sync_async","argCount":0,"args":[2,2,],"documentation":"","externalName":"__crystal_create_sync_async","help":"","hidden":false,"kind":1,"name":"__crystal_create_sync_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_destroy_sync_async","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_destroy_sync_async","help":"","hidden":false,"kind":1,"name":"__crystal_destroy_sync_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_set_variable_sync_async","argCount":0,"args":[2,1,1,],"documentation":"","externalName":"__crystal_set_variable_sync_async","help":"","hidden":false,"kind":1,"name":"__crystal_set_variable_sync_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_remove_variable_sync_async","argCount":0,"args":[2,1,],"documentation":"","externalName":"__crystal_remove_variable_sync_async","help":"","hidden":false,"kind":1,"name":"__crystal_remove_variable_sync_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_get_variable_other_sync_async","argCount":0,"args":[2,2,1,],"documentation":"","externalName":"__crystal_get_variable_other_sync_async","help":"","hidden":false,"kind":1,"name":"__crystal_get_variable_other_sync_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_iter_other_syncs_async","argCount":0,"args":[],"documentation":"","externalName":"__crystal_iter_other_syncs_async","help":"","hidden":false,"kind":1,"name":"__crystal_iter_other_syncs_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_is_player_admin_async","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_is_player_admin_async","help":"","hidden":false,"kind":1,"name":"__crystal_is_player_admin_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_get_player_admin_async","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_get_player_admin_async","help":"","hidden":false,"kind":1,"name":"__crystal_get_player_admin_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_player_kick_async","argCount":0,"args":[2,1,],"documentation":"","externalName":"__crystal_player_kick_async","help":"","hidden":false,"kind":1,"name":"__crystal_player_kick_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_player_ban_async","argCount":0,"args":[2,1,2,],"documentation":"","externalName":"__crystal_player_ban_async","help":"","hidden":false,"kind":1,"name":"__crystal_player_ban_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_player_unban_async","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_player_unban_async","help":"","hidden":false,"kind":1,"name":"__crystal_player_unban_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_logout_async","argCount":0,"args":[],"documentation":"","externalName":"__crystal_logout_async","help":"","hidden":false,"kind":1,"name":"__crystal_logout_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_request_other_sync_variable_async","argCount":0,"args":[2,2,1,2,],"documentation":"","externalName":"__crystal_request_other_sync_variable_async","help":"","hidden":false,"kind":1,"name":"__crystal_request_other_sync_variable_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_fetch_bdb_async","argCount":0,"args":[1,],"documentation":"","externalName":"__crystal_fetch_bdb_async","help":"","hidden":false,"kind":1,"name":"__crystal_fetch_bdb_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_set_bdb_async","argCount":0,"args":[1,1,],"documentation":"","externalName":"__crystal_set_bdb_async","help":"","hidden":false,"kind":1,"name":"__crystal_set_bdb_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_get_incoming_friends_async","argCount":0,"args":[],"documentation":"","externalName":"__crystal_get_incoming_friends_async","help":"","hidden":false,"kind":1,"name":"__crystal_get_incoming_friends_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_get_outgoing_friends_async","argCount":0,"args":[],"documentation":"","externalName":"__crystal_get_outgoing_friends_async","help":"","hidden":false,"kind":1,"name":"__crystal_get_outgoing_friends_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_get_friends_async","argCount":0,"args":[],"documentation":"","externalName":"__crystal_get_friends_async","help":"","hidden":false,"kind":1,"name":"__crystal_get_friends_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_send_outgoing_friend_async","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_send_outgoing_friend_async","help":"","hidden":false,"kind":1,"name":"__crystal_send_outgoing_friend_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_remove_outgoing_friend_async","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_remove_outgoing_friend_async","help":"","hidden":false,"kind":1,"name":"__crystal_remove_outgoing_friend_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_deny_incoming_friend_async","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_deny_incoming_friend_async","help":"","hidden":false,"kind":1,"name":"__crystal_deny_incoming_friend_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_accept_incoming_friend_async","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_accept_incoming_friend_async","help":"","hidden":false,"kind":1,"name":"__crystal_accept_incoming_friend_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_remove_friend_async","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_remove_friend_async","help":"","hidden":false,"kind":1,"name":"__crystal_remove_friend_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"RegisterCallbacks","argCount":0,"args":[1,1,1,1,],"documentation":"","externalName":"RegisterCallbacks","help":"","hidden":false,"kind":1,"name":"RegisterCallbacks","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_set_async_events","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_set_async_events","help":"","hidden":false,"kind":1,"name":"__crystal_set_async_events","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_set_notification_limit","argCount":0,"args":[2,2,],"documentation":"","externalName":"__crystal_set_notification_limit","help":"","hidden":false,"kind":1,"name":"__crystal_set_notification_limit","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_get_dropped_notifications","argCount":0,"args":[],"documentation":"","externalName":"__crystal_get_dropped_notifications","help":"","hidden":false,"kind":1,"name":"__crystal_get_dropped_notifications","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":1,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_reset_dropped_notifications","argCount":0,"args":[],"documentation":"","externalName":"__crystal_reset_dropped_notifications","help":"","hidden":false,"kind":1,"name":"__crystal_reset_dropped_notifications","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_set_coalescing","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_set_coalescing","help":"","hidden":false,"kind":1,"name":"__crystal_set_coalescing","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_get_notifications","argCount":0,"args":[],"documentation":"","externalName":"__crystal_get_notifications","help":"","hidden":false,"kind":1,"name":"__crystal_get_notifications","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":1,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_drain_notifications","argCount":0,"args":[1,2,],"documentation":"","externalName":"__crystal_drain_notifications","help":"","hidden":false,"kind":1,"name":"__crystal_drain_notifications","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_get_notification_count","argCount":0,"args":[],"documentation":"","externalName":"__crystal_get_notification_count","help":"","hidden":false,"kind":1,"name":"__crystal_get_notification_count","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_drain_notifications_binary","argCount":0,"args":[1,2,],"documentation":"","externalName":"__crystal_drain_notifications_binary","help":"","hidden":false,"kind":1,"name":"__crystal_drain_notifications_binary","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_set_subscriptions","argCount":0,"args":[1,],"documentation":"","externalName":"__crystal_set_subscriptions","help":"","hidden":false,"kind":1,"name":"__crystal_set_subscriptions","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":1,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_get_subscriptions","argCount":0,"args":[],"documentation":"","externalName":"__crystal_get_subscriptions","help":"","hidden":false,"kind":1,"name":"__crystal_get_subscriptions","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":1,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_get_last_decode_error","argCount":0,"args":[],"documentation":"","externalName":"__crystal_get_last_decode_error","help":"","hidden":false,"kind":1,"name":"__crystal_get_last_decode_error","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":1,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_set_value_format","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_set_value_format","help":"","hidden":false,"kind":1,"name":"__crystal_set_value_format","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_set_lossless_values","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_set_lossless_values","help":"","hidden":false,"kind":1,"name":"__crystal_set_lossless_values","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_set_canonical_values","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_set_canonical_values","help":"","hidden":false,"kind":1,"name":"__crystal_set_canonical_values","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_json_to_value","argCount":0,"args":[1,],"documentation":"","externalName":"__crystal_json_to_value","help":"","hidden":false,"kind":1,"name":"__crystal_json_to_value","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":1,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_value_to_json","argCount":0,"args":[1,],"documentation":"","externalName":"__crystal_value_to_json","help":"","hidden":false,"kind":1,"name":"__crystal_value_to_json","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":1,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_set_json_output","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_set_json_output","help":"","hidden":false,"kind":1,"name":"__crystal_set_json_output","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_configure","argCount":0,"args":[1,1,],"documentation":"","externalName":"__crystal_configure","help":"","hidden":false,"kind":1,"name":"__crystal_configure","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":1,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_shutdown","argCount":0,"args":[],"documentation":"","externalName":"__crystal_shutdown","help":"","hidden":false,"kind":1,"name":"__crystal_shutdown","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_create","argCount":0,"args":[1,],"documentation":"","externalName":"__crystal_client_create","help":"","hidden":false,"kind":1,"name":"__crystal_client_create","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_destroy","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_client_destroy","help":"","hidden":false,"kind":1,"name":"__crystal_client_destroy","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_exists","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_client_exists","help":"","hidden":false,"kind":1,"name":"__crystal_client_exists","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_set_room","argCount":0,"args":[2,1,],"documentation":"","externalName":"__crystal_client_set_room","help":"","hidden":false,"kind":1,"name":"__crystal_client_set_room","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_init","argCount":0,"args":[2,1,],"documentation":"","externalName":"__crystal_client_init","help":"","hidden":false,"kind":1,"name":"__crystal_client_init","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_connect","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_client_connect","help":"","hidden":false,"kind":1,"name":"__crystal_client_connect","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_update","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_client_update","help":"","hidden":false,"kind":1,"name":"__crystal_client_update","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_is_connected","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_client_is_connected","help":"","hidden":false,"kind":1,"name":"__crystal_client_is_connected","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_is_connecting","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_client_is_connecting","help":"","hidden":false,"kind":1,"name":"__crystal_client_is_connecting","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_is_loggedin","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_client_is_loggedin","help":"","hidden":false,"kind":1,"name":"__crystal_client_is_loggedin","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_get_ping","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_client_get_ping","help":"","hidden":false,"kind":1,"name":"__crystal_client_get_ping","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_set_game_token","argCount":0,"args":[2,1,],"documentation":"","externalName":"__crystal_client_set_game_token","help":"","hidden":false,"kind":1,"name":"__crystal_client_set_game_token","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_disconnect","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_client_disconnect","help":"","hidden":false,"kind":1,"name":"__crystal_client_disconnect","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_login","argCount":0,"args":[2,1,1,],"documentation":"","externalName":"__crystal_client_login","help":"","hidden":false,"kind":1,"name":"__crystal_client_login","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_login_with_token","argCount":0,"args":[2,1,1,],"documentation":"","externalName":"__crystal_client_login_with_token","help":"","hidden":false,"kind":1,"name":"__crystal_client_login_with_token","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_register","argCount":0,"args":[2,1,1,1,1,],"documentation":"","externalName":"__crystal_client_register","help":"","hidden":false,"kind":1,"name":"__crystal_client_register","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_get_player_id","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_client_get_player_id","help":"","hidden":false,"kind":1,"name":"__crystal_client_get_player_id","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_get_player_name","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_client_get_player_name","help":"","hidden":false,"kind":1,"name":"__crystal_client_get_player_name","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":1,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_set_variable","argCount":0,"args":[2,1,1,],"documentation":"","externalName":"__crystal_client_set_variable","help":"","hidden":false,"kind":1,"name":"__crystal_client_set_variable","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_remove_variable","argCount":0,"args":[2,1,],"documentation":"","externalName":"__crystal_client_remove_variable","help":"","hidden":false,"kind":1,"name":"__crystal_client_remove_variable","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_iter_other_players","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_client_iter_other_players","help":"","hidden":false,"kind":1,"name":"__crystal_client_iter_other_players","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":1,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_other_player_count","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_client_other_player_count","help":"","hidden":false,"kind":1,"name":"__crystal_client_other_player_count","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_get_other_player","argCount":0,"args":[2,2,],"documentation":"","externalName":"__crystal_client_get_other_player","help":"","hidden":false,"kind":1,"name":"__crystal_client_get_other_player","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":1,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_get_other_player_name","argCount":0,"args":[2,1,],"documentation":"","externalName":"__crystal_client_get_other_player_name","help":"","hidden":false,"kind":1,"name":"__crystal_client_get_other_player_name","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":1,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_request_other_player_variable","argCount":0,"args":[2,2,1,2,],"documentation":"","externalName":"__crystal_client_request_other_player_variable","help":"","hidden":false,"kind":1,"name":"__crystal_client_request_other_player_variable","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_p2p","argCount":0,"args":[2,2,2,1,],"documentation":"","externalName":"__crystal_client_p2p","help":"","hidden":false,"kind":1,"name":"__crystal_client_p2p","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_set_version","argCount":0,"args":[2,2,],"documentation":"","externalName":"__crystal_client_set_version","help":"","hidden":false,"kind":1,"name":"__crystal_client_set_version","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_get_version","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_client_get_version","help":"","hidden":false,"kind":1,"name":"__crystal_client_get_version","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_get_server_version","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_client_get_server_version","help":"","hidden":false,"kind":1,"name":"__crystal_client_get_server_version","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_set_session","argCount":0,"args":[2,1,],"documentation":"","externalName":"__crystal_client_set_session","help":"","hidden":false,"kind":1,"name":"__crystal_client_set_session","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_get_session","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_client_get_session","help":"","hidden":false,"kind":1,"name":"__crystal_client_get_session","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":1,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_get_open_playerini","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_client_get_open_playerini","help":"","hidden":false,"kind":1,"name":"__crystal_client_get_open_playerini","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":1,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_open_playerini","argCount":0,"args":[2,1,],"documentation":"","externalName":"__crystal_client_open_playerini","help":"","hidden":false,"kind":1,"name":"__crystal_client_open_playerini","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_close_playerini","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_client_close_playerini","help":"","hidden":false,"kind":1,"name":"__crystal_client_close_playerini","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_has_playerini","argCount":0,"args":[2,1,1,],"documentation":"","externalName":"__crystal_client_has_playerini","help":"","hidden":false,"kind":1,"name":"__crystal_client_has_playerini","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_get_playerini","argCount":0,"args":[2,1,1,],"documentation":"","externalName":"__crystal_client_get_playerini","help":"","hidden":false,"kind":1,"name":"__crystal_client_get_playerini","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":1,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_set_playerini","argCount":0,"args":[2,1,1,1,],"documentation":"","externalName":"__crystal_client_set_playerini","help":"","hidden":false,"kind":1,"name":"__crystal_client_set_playerini","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_remove_playerini","argCount":0,"args":[2,1,1,],"documentation":"","externalName":"__crystal_client_remove_playerini","help":"","hidden":false,"kind":1,"name":"__crystal_client_remove_playerini","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_get_open_gameini","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_client_get_open_gameini","help":"","hidden":false,"kind":1,"name":"__crystal_client_get_open_gameini","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":1,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_open_gameini","argCount":0,"args":[2,1,],"documentation":"","externalName":"__crystal_client_open_gameini","help":"","hidden":false,"kind":1,"name":"__crystal_client_open_gameini","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_close_gameini","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_client_close_gameini","help":"","hidden":false,"kind":1,"name":"__crystal_client_close_gameini","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_has_gameini","argCount":0,"args":[2,1,1,],"documentation":"","externalName":"__crystal_client_has_gameini","help":"","hidden":false,"kind":1,"name":"__crystal_client_has_gameini","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_get_gameini","argCount":0,"args":[2,1,1,],"documentation":"","externalName":"__crystal_client_get_gameini","help":"","hidden":false,"kind":1,"name":"__crystal_client_get_gameini","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":1,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_set_gameini","argCount":0,"args":[2,1,1,1,],"documentation":"","externalName":"__crystal_client_set_gameini","help":"","hidden":false,"kind":1,"name":"__crystal_client_set_gameini","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_remove_gameini","argCount":0,"args":[2,1,1,],"documentation":"","externalName":"__crystal_client_remove_gameini","help":"","hidden":false,"kind":1,"name":"__crystal_client_remove_gameini","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_has_achievement","argCount":0,"args":[2,2,],"documentation":"","externalName":"__crystal_client_has_achievement","help":"","hidden":false,"kind":1,"name":"__crystal_client_has_achievement","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_get_achievement","argCount":0,"args":[2,2,],"documentation":"","externalName":"__crystal_client_get_achievement","help":"","hidden":false,"kind":1,"name":"__crystal_client_get_achievement","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":1,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_has_reached_achievement","argCount":0,"args":[2,2,],"documentation":"","externalName":"__crystal_client_has_reached_achievement","help":"","hidden":false,"kind":1,"name":"__crystal_client_has_reached_achievement","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_get_reached_achievement","argCount":0,"args":[2,2,],"documentation":"","externalName":"__crystal_client_get_reached_achievement","help":"","hidden":false,"kind":1,"name":"__crystal_client_get_reached_achievement","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_reach_achievement","argCount":0,"args":[2,2,],"documentation":"","externalName":"__crystal_client_reach_achievement","help":"","hidden":false,"kind":1,"name":"__crystal_client_reach_achievement","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_has_highscore","argCount":0,"args":[2,2,],"documentation":"","externalName":"__crystal_client_has_highscore","help":"","hidden":false,"kind":1,"name":"__crystal_client_has_highscore","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_get_highscore","argCount":0,"args":[2,2,],"documentation":"","externalName":"__crystal_client_get_highscore","help":"","hidden":false,"kind":1,"name":"__crystal_client_get_highscore","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":1,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_has_score_highscore","argCount":0,"args":[2,2,],"documentation":"","externalName":"__crystal_client_has_score_highscore","help":"","hidden":false,"kind":1,"name":"__crystal_client_has_score_highscore","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_get_score_highscore","argCount":0,"args":[2,2,],"documentation":"","externalName":"__crystal_client_get_score_highscore","help":"","hidden":false,"kind":1,"name":"__crystal_client_get_score_highscore","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_set_score_highscore","argCount":0,"args":[2,2,2,],"documentation":"","externalName":"__crystal_client_set_score_highscore","help":"","hidden":false,"kind":1,"name":"__crystal_client_set_score_highscore","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_create_sync","argCount":0,"args":[2,2,2,],"documentation":"","externalName":"__crystal_client_create_sync","help":"","hidden":false,"kind":1,"name":"__crystal_client_create_sync","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_destroy_sync","argCount":0,"args":[2,2,],"documentation":"","externalName":"__crystal_client_destroy_sync","help":"","hidden":false,"kind":1,"name":"__crystal_client_destroy_sync","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_set_variable_sync","argCount":0,"args":[2,2,1,1,],"documentation":"","externalName":"__crystal_client_set_variable_sync","help":"","hidden":false,"kind":1,"name":"__crystal_client_set_variable_sync","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_remove_variable_sync","argCount":0,"args":[2,2,1,],"documentation":"","externalName":"__crystal_client_remove_variable_sync","help":"","hidden":false,"kind":1,"name":"__crystal_client_remove_variable_sync","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_get_variable_other_sync","argCount":0,"args":[2,2,2,1,],"documentation":"","externalName":"__crystal_client_get_variable_other_sync","help":"","hidden":false,"kind":1,"name":"__crystal_client_get_variable_other_sync","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":1,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_iter_other_syncs","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_client_iter_other_syncs","help":"","hidden":false,"kind":1,"name":"__crystal_client_iter_other_syncs","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":1,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_is_player_admin","argCount":0,"args":[2,2,],"documentation":"","externalName":"__crystal_client_is_player_admin","help":"","hidden":false,"kind":1,"name":"__crystal_client_is_player_admin","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_get_player_admin","argCount":0,"args":[2,2,],"documentation":"","externalName":"__crystal_client_get_player_admin","help":"","hidden":false,"kind":1,"name":"__crystal_client_get_player_admin","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":1,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_player_kick","argCount":0,"args":[2,2,1,],"documentation":"","externalName":"__crystal_client_player_kick","help":"","hidden":false,"kind":1,"name":"__crystal_client_player_kick","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_player_ban","argCount":0,"args":[2,2,1,2,],"documentation":"","externalName":"__crystal_client_player_ban","help":"","hidden":false,"kind":1,"name":"__crystal_client_player_ban","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_player_unban","argCount":0,"args":[2,2,],"documentation":"","externalName":"__crystal_client_player_unban","help":"","hidden":false,"kind":1,"name":"__crystal_client_player_unban","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_logout","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_client_logout","help":"","hidden":false,"kind":1,"name":"__crystal_client_logout","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_request_other_sync_variable","argCount":0,"args":[2,2,2,1,2,],"documentation":"","externalName":"__crystal_client_request_other_sync_variable","help":"","hidden":false,"kind":1,"name":"__crystal_client_request_other_sync_variable","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_fetch_bdb","argCount":0,"args":[2,1,],"documentation":"","externalName":"__crystal_client_fetch_bdb","help":"","hidden":false,"kind":1,"name":"__crystal_client_fetch_bdb","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_set_bdb","argCount":0,"args":[2,1,1,],"documentation":"","externalName":"__crystal_client_set_bdb","help":"","hidden":false,"kind":1,"name":"__crystal_client_set_bdb","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_get_incoming_friends","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_client_get_incoming_friends","help":"","hidden":false,"kind":1,"name":"__crystal_client_get_incoming_friends","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":1,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_get_outgoing_friends","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_client_get_outgoing_friends","help":"","hidden":false,"kind":1,"name":"__crystal_client_get_outgoing_friends","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":1,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_get_friends","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_client_get_friends","help":"","hidden":false,"kind":1,"name":"__crystal_client_get_friends","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":1,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_send_outgoing_friend","argCount":0,"args":[2,2,],"documentation":"","externalName":"__crystal_client_send_outgoing_friend","help":"","hidden":false,"kind":1,"name":"__crystal_client_send_outgoing_friend","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_remove_outgoing_friend","argCount":0,"args":[2,2,],"documentation":"","externalName":"__crystal_client_remove_outgoing_friend","help":"","hidden":false,"kind":1,"name":"__crystal_client_remove_outgoing_friend","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_deny_incoming_friend","argCount":0,"args":[2,2,],"documentation":"","externalName":"__crystal_client_deny_incoming_friend","help":"","hidden":false,"kind":1,"name":"__crystal_client_deny_incoming_friend","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_accept_incoming_friend","argCount":0,"args":[2,2,],"documentation":"","externalName":"__crystal_client_accept_incoming_friend","help":"","hidden":false,"kind":1,"name":"__crystal_client_accept_incoming_friend","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_remove_friend","argCount":0,"args":[2,2,],"documentation":"","externalName":"__crystal_client_remove_friend","help":"","hidden":false,"kind":1,"name":"__crystal_client_remove_friend","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_update_async","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_client_update_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_update_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_is_connected_async","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_client_is_connected_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_is_connected_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_is_connecting_async","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_client_is_connecting_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_is_connecting_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_is_loggedin_async","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_client_is_loggedin_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_is_loggedin_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_get_ping_async","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_client_get_ping_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_get_ping_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_set_game_token_async","argCount":0,"args":[2,1,],"documentation":"","externalName":"__crystal_client_set_game_token_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_set_game_token_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_disconnect_async","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_client_disconnect_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_disconnect_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_login_async","argCount":0,"args":[2,1,1,],"documentation":"","externalName":"__crystal_client_login_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_login_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_login_with_token_async","argCount":0,"args":[2,1,1,],"documentation":"","externalName":"__crystal_client_login_with_token_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_login_with_token_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_register_async","argCount":0,"args":[2,1,1,1,1,],"documentation":"","externalName":"__crystal_client_register_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_register_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_get_player_id_async","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_client_get_player_id_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_get_player_id_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_get_player_name_async","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_client_get_player_name_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_get_player_name_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_set_variable_async","argCount":0,"args":[2,1,1,],"documentation":"","externalName":"__crystal_client_set_variable_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_set_variable_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_remove_variable_async","argCount":0,"args":[2,1,],"documentation":"","externalName":"__crystal_client_remove_variable_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_remove_variable_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_iter_other_players_async","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_client_iter_other_players_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_iter_other_players_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_other_player_count_async","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_client_other_player_count_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_other_player_count_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_get_other_player_async","argCount":0,"args":[2,2,],"documentation":"","externalName":"__crystal_client_get_other_player_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_get_other_player_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_get_other_player_name_async","argCount":0,"args":[2,1,],"documentation":"","externalName":"__crystal_client_get_other_player_name_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_get_other_player_name_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_request_other_player_variable_async","argCount":0,"args":[2,2,1,2,],"documentation":"","externalName":"__crystal_client_request_other_player_variable_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_request_other_player_variable_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_p2p_async","argCount":0,"args":[2,2,2,1,],"documentation":"","externalName":"__crystal_client_p2p_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_p2p_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_set_version_async","argCount":0,"args":[2,2,],"documentation":"","externalName":"__crystal_client_set_version_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_set_version_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_get_version_async","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_client_get_version_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_get_version_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_get_server_version_async","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_client_get_server_version_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_get_server_version_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_set_session_async","argCount":0,"args":[2,1,],"documentation":"","externalName":"__crystal_client_set_session_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_set_session_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_get_session_async","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_client_get_session_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_get_session_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_get_open_playerini_async","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_client_get_open_playerini_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_get_open_playerini_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_open_playerini_async","argCount":0,"args":[2,1,],"documentation":"","externalName":"__crystal_client_open_playerini_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_open_playerini_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_close_playerini_async","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_client_close_playerini_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_close_playerini_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_has_playerini_async","argCount":0,"args":[2,1,1,],"documentation":"","externalName":"__crystal_client_has_playerini_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_has_playerini_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_get_playerini_async","argCount":0,"args":[2,1,1,],"documentation":"","externalName":"__crystal_client_get_playerini_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_get_playerini_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_set_playerini_async","argCount":0,"args":[2,1,1,1,],"documentation":"","externalName":"__crystal_client_set_playerini_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_set_playerini_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_remove_playerini_async","argCount":0,"args":[2,1,1,],"documentation":"","externalName":"__crystal_client_remove_playerini_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_remove_playerini_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_get_open_gameini_async","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_client_get_open_gameini_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_get_open_gameini_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_open_gameini_async","argCount":0,"args":[2,1,],"documentation":"","externalName":"__crystal_client_open_gameini_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_open_gameini_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_close_gameini_async","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_client_close_gameini_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_close_gameini_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_has_gameini_async","argCount":0,"args":[2,1,1,],"documentation":"","externalName":"__crystal_client_has_gameini_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_has_gameini_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_get_gameini_async","argCount":0,"args":[2,1,1,],"documentation":"","externalName":"__crystal_client_get_gameini_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_get_gameini_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_set_gameini_async","argCount":0,"args":[2,1,1,1,],"documentation":"","externalName":"__crystal_client_set_gameini_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_set_gameini_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_remove_gameini_async","argCount":0,"args":[2,1,1,],"documentation":"","externalName":"__crystal_client_remove_gameini_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_remove_gameini_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_has_achievement_async","argCount":0,"args":[2,2,],"documentation":"","externalName":"__crystal_client_has_achievement_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_has_achievement_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_get_achievement_async","argCount":0,"args":[2,2,],"documentation":"","externalName":"__crystal_client_get_achievement_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_get_achievement_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_has_reached_achievement_async","argCount":0,"args":[2,2,],"documentation":"","externalName":"__crystal_client_has_reached_achievement_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_has_reached_achievement_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_get_reached_achievement_async","argCount":0,"args":[2,2,],"documentation":"","externalName":"__crystal_client_get_reached_achievement_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_get_reached_achievement_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_reach_achievement_async","argCount":0,"args":[2,2,],"documentation":"","externalName":"__crystal_client_reach_achievement_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_reach_achievement_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_has_highscore_async","argCount":0,"args":[2,2,],"documentation":"","externalName":"__crystal_client_has_highscore_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_has_highscore_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_get_highscore_async","argCount":0,"args":[2,2,],"documentation":"","externalName":"__crystal_client_get_highscore_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_get_highscore_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_has_score_highscore_async","argCount":0,"args":[2,2,],"documentation":"","externalName":"__crystal_client_has_score_highscore_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_has_score_highscore_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_get_score_highscore_async","argCount":0,"args":[2,2,],"documentation":"","externalName":"__crystal_client_get_score_highscore_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_get_score_highscore_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_set_score_highscore_async","argCount":0,"args":[2,2,2,],"documentation":"","externalName":"__crystal_client_set_score_highscore_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_set_score_highscore_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_create_sync_async","argCount":0,"args":[2,2,2,],"documentation":"","externalName":"__crystal_client_create_sync_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_create_sync_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_destroy_sync_async","argCount":0,"args":[2,2,],"documentation":"","externalName":"__crystal_client_destroy_sync_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_destroy_sync_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_set_variable_sync_async","argCount":0,"args":[2,2,1,1,],"documentation":"","externalName":"__crystal_client_set_variable_sync_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_set_variable_sync_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_remove_variable_sync_async","argCount":0,"args":[2,2,1,],"documentation":"","externalName":"__crystal_client_remove_variable_sync_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_remove_variable_sync_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_get_variable_other_sync_async","argCount":0,"args":[2,2,2,1,],"documentation":"","externalName":"__crystal_client_get_variable_other_sync_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_get_variable_other_sync_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_iter_other_syncs_async","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_client_iter_other_syncs_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_iter_other_syncs_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_is_player_admin_async","argCount":0,"args":[2,2,],"documentation":"","externalName":"__crystal_client_is_player_admin_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_is_player_admin_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_get_player_admin_async","argCount":0,"args":[2,2,],"documentation":"","externalName":"__crystal_client_get_player_admin_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_get_player_admin_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_player_kick_async","argCount":0,"args":[2,2,1,],"documentation":"","externalName":"__crystal_client_player_kick_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_player_kick_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_player_ban_async","argCount":0,"args":[2,2,1,2,],"documentation":"","externalName":"__crystal_client_player_ban_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_player_ban_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_player_unban_async","argCount":0,"args":[2,2,],"documentation":"","externalName":"__crystal_client_player_unban_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_player_unban_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_logout_async","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_client_logout_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_logout_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_request_other_sync_variable_async","argCount":0,"args":[2,2,2,1,2,],"documentation":"","externalName":"__crystal_client_request_other_sync_variable_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_request_other_sync_variable_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_fetch_bdb_async","argCount":0,"args":[2,1,],"documentation":"","externalName":"__crystal_client_fetch_bdb_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_fetch_bdb_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_set_bdb_async","argCount":0,"args":[2,1,1,],"documentation":"","externalName":"__crystal_client_set_bdb_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_set_bdb_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_get_incoming_friends_async","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_client_get_incoming_friends_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_get_incoming_friends_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_get_outgoing_friends_async","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_client_get_outgoing_friends_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_get_outgoing_friends_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_get_friends_async","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_client_get_friends_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_get_friends_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_send_outgoing_friend_async","argCount":0,"args":[2,2,],"documentation":"","externalName":"__crystal_client_send_outgoing_friend_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_send_outgoing_friend_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_remove_outgoing_friend_async","argCount":0,"args":[2,2,],"documentation":"","externalName":"__crystal_client_remove_outgoing_friend_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_remove_outgoing_friend_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_deny_incoming_friend_async","argCount":0,"args":[2,2,],"documentation":"","externalName":"__crystal_client_deny_incoming_friend_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_deny_incoming_friend_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_accept_incoming_friend_async","argCount":0,"args":[2,2,],"documentation":"","externalName":"__crystal_client_accept_incoming_friend_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_accept_incoming_friend_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_remove_friend_async","argCount":0,"args":[2,2,],"documentation":"","externalName":"__crystal_client_remove_friend_async","help":"","hidden":false,"kind":1,"name":"__crystal_client_remove_friend_async","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_set_subscriptions","argCount":0,"args":[2,1,],"documentation":"","externalName":"__crystal_client_set_subscriptions","help":"","hidden":false,"kind":1,"name":"__crystal_client_set_subscriptions","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":1,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_get_subscriptions","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_client_get_subscriptions","help":"","hidden":false,"kind":1,"name":"__crystal_client_get_subscriptions","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":1,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_set_async_events","argCount":0,"args":[2,2,],"documentation":"","externalName":"__crystal_client_set_async_events","help":"","hidden":false,"kind":1,"name":"__crystal_client_set_async_events","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_get_notification","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_client_get_notification","help":"","hidden":false,"kind":1,"name":"__crystal_client_get_notification","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":1,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_get_notifications","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_client_get_notifications","help":"","hidden":false,"kind":1,"name":"__crystal_client_get_notifications","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":1,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_drain_notifications","argCount":0,"args":[2,1,2,],"documentation":"","externalName":"__crystal_client_drain_notifications","help":"","hidden":false,"kind":1,"name":"__crystal_client_drain_notifications","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_drain_notifications_binary","argCount":0,"args":[2,1,2,],"documentation":"","externalName":"__crystal_client_drain_notifications_binary","help":"","hidden":false,"kind":1,"name":"__crystal_client_drain_notifications_binary","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_get_notification_count","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_client_get_notification_count","help":"","hidden":false,"kind":1,"name":"__crystal_client_get_notification_count","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_set_notification_limit","argCount":0,"args":[2,2,2,],"documentation":"","externalName":"__crystal_client_set_notification_limit","help":"","hidden":false,"kind":1,"name":"__crystal_client_set_notification_limit","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_get_dropped_notifications","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_client_get_dropped_notifications","help":"","hidden":false,"kind":1,"name":"__crystal_client_get_dropped_notifications","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":1,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_set_coalescing","argCount":0,"args":[2,2,],"documentation":"","externalName":"__crystal_client_set_coalescing","help":"","hidden":false,"kind":1,"name":"__crystal_client_set_coalescing","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_reset_dropped_notifications","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_client_reset_dropped_notifications","help":"","hidden":false,"kind":1,"name":"__crystal_client_reset_dropped_notifications","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_update_budget","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_update_budget","help":"","hidden":false,"kind":1,"name":"__crystal_update_budget","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_update_budget","argCount":0,"args":[2,2,],"documentation":"","externalName":"__crystal_client_update_budget","help":"","hidden":false,"kind":1,"name":"__crystal_client_update_budget","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_advance_time","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_advance_time","help":"","hidden":false,"kind":1,"name":"__crystal_advance_time","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_get_last_connection_error","argCount":0,"args":[],"documentation":"","externalName":"__crystal_get_last_connection_error","help":"","hidden":false,"kind":1,"name":"__crystal_get_last_connection_error","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":1,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_get_last_connection_error","argCount":0,"args":[2,],"documentation":"","externalName":"__crystal_client_get_last_connection_error","help":"","hidden":false,"kind":1,"name":"__crystal_client_get_last_connection_error","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":1,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_set_reconnect_policy","argCount":0,"args":[2,2,2,2,],"documentation":"","externalName":"__crystal_set_reconnect_policy","help":"","hidden":false,"kind":1,"name":"__crystal_set_reconnect_policy","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
+        {"$GMExtensionFunction":"","%Name":"__crystal_client_set_reconnect_policy","argCount":0,"args":[2,2,2,2,2,],"documentation":"","externalName":"__crystal_client_set_reconnect_policy","help":"","hidden":false,"kind":1,"name":"__crystal_client_set_reconnect_policy","resourceType":"GMExtensionFunction","resourceVersion":"2.0","returnType":2,},
       ],"init":"","kind":1,"name":"","order":[],"origname":"","ProxyFiles":[],"resourceType":"GMExtensionFile","resourceVersion":"2.0","uncompress":false,"usesRunnerInterface":false,},
   ],
   "gradleinject":null,
@@ -54,6 +54,18 @@ enum P2PCode {
     Server = -4,
 }
 
+enum NotificationOverflow {
+    DropOldest = 0,
+    DropNewest = 1,
+    Coalesce = 2,
+}
+
+enum ReconnectStrategy {
+    Off = 0,
+    Fixed = 1,
+    Exponential = 2,
+}
+
 function CrystalPlayer() constructor {
     id = -1;
     name = "";
@@ -93,11 +105,22 @@ global.__crystal_callback_login = undefined;
 global.__crystal_callback_banned = undefined;
 global.__crystal_callback_kicked = undefined;
 global.__crystal_callback_disconnected = undefined;
+global.__crystal_callback_connect_result = undefined;
+global.__crystal_callback_reconnecting = undefined;
+global.__crystal_callback_reconnect_failed = undefined;
 global.__crystal_callback_login_token = undefined;
 //global.__crystal_callback_data_update = undefined;
 global.__crystal_callback_bdb = undefined;
 global.__crystal_callback_update_variable = undefined;
 global.__crystal_callback_update_sync_variable = undefined;
+global.__crystal_callback_missed_notifications = undefined;
+global.__crystal_tickets = {};
+global.__crystal_last_seq = 0;
+global.__crystal_drain_buffer = undefined;
+global.__crystal_drain_left = 0;
+global.__crystal_lossless_values = false;
+global.__crystal_json_output = false;
+global.__crystal_bits_buffer = buffer_create(8, buffer_fixed, 1);
 
 function crystal_set_callback_room(callback) {
     global.__crystal_callback_room = callback;
@@ -127,6 +150,24 @@ function crystal_set_callback_disconnected(callback) {
     global.__crystal_callback_disconnected = callback;
 }
 
+/// Called once each crystal_connect() succeeded or failed, with whether it succeeded, the kind
+/// of failure ("dns", "refused", "handshake" or "version", "" on success) and a message
+/// describing it. The client stays connected after a "version" failure.
+function crystal_set_callback_connect_result(callback) {
+    global.__crystal_callback_connect_result = callback;
+}
+
+/// Called before each attempt to reconnect, with its number (from 1) and the milliseconds until
+/// it's made. Each attempt then ends with the connect result callback.
+function crystal_set_callback_reconnecting(callback) {
+    global.__crystal_callback_reconnecting = callback;
+}
+
+/// Called with the number of attempts made once reconnecting gave up.
+function crystal_set_callback_reconnect_failed(callback) {
+    global.__crystal_callback_reconnect_failed = callback;
+}
+
 function crystal_set_callback_login_token(callback) {
     global.__crystal_callback_login_token = callback;
 }
@@ -147,123 +188,563 @@ function crystal_set_callback_update_sync_variable(callback) {
     global.__crystal_callback_update_sync_variable = callback;
 }
 
+/// Called with the sequence number of the first missed notification and how many were missed.
+function crystal_set_callback_missed_notifications(callback) {
+    global.__crystal_callback_missed_notifications = callback;
+}
+
+/// Changes a setting, call before crystal_init() for the ones about the runtime or logging:
+/// "runtime" ("multi_thread", "current_thread" or "manual"), "worker_threads", "log_level" ("off", "error",
+/// "warn", "info", "debug" or "trace"), "notification_limit", "notification_policy"
+/// ("drop_oldest", "drop_newest" or "coalesce"), "call_timeout" (milliseconds, 0 for none) and
+/// "shutdown_timeout" (milliseconds crystal_shutdown() waits, 1000 by default).
+/// Returns why the setting couldn't be changed, or "" if it was.
+function crystal_configure(key, value) {
+    return __crystal_configure(key, is_string(value) ? value : string(value));
+}
+
+/// Moves the clock forward by `ms` milliseconds when the "runtime" is "manual", which stops it
+/// otherwise, so timeouts and reconnect delays can be stepped through in tests. Blocking calls
+/// never time out then, as the clock can't move while they wait. Needs a DLL built with the
+/// manual-time feature, returns false when the runtime isn't "manual".
+function crystal_advance_time(ms) {
+    return __crystal_advance_time(ms);
+}
+
+/// Can be called again to start over, for example with another game_id. Notifications that
+/// weren't handled yet are dropped then.
 function crystal_init(game_id) {
+    // Sequence numbers start over along with the client.
+    global.__crystal_last_seq = 0;
+    global.__crystal_drain_left = 0;
     return __crystal_init(game_id);
 }
 
+/// Disconnects and stops the client, waiting up to "shutdown_timeout" for pending calls.
+/// The game ending does this too. crystal_init() can be called again afterwards.
+function crystal_shutdown() {
+    return __crystal_shutdown();
+}
+
+/// Creates another client, with its own connection, login and notifications, for playing with
+/// several accounts at once. Returns its handle.
+/// Every __crystal_* call has a __crystal_client_* form taking the handle first, for example
+/// __crystal_client_connect(handle) or __crystal_client_get_notification(handle). The
+/// crystal_* functions only act on the default client.
+function crystal_client_create(game_id) {
+    return __crystal_client_create(game_id);
+}
+
+/// Disconnects and removes a client made with crystal_client_create().
+function crystal_client_destroy(handle) {
+    return __crystal_client_destroy(handle);
+}
+
+function crystal_client_exists(handle) {
+    return __crystal_client_exists(handle);
+}
+
 function crystal_connect() {
     return __crystal_connect();
 }
 
+/// Why the last crystal_connect() failed, as "category: message", or "" if it didn't.
+function crystal_get_last_connection_error() {
+    return __crystal_get_last_connection_error();
+}
+
+/// Reconnects by itself after an established connection dropped, as `strategy` (a
+/// ReconnectStrategy) says: Fixed retries every `delay` milliseconds, Exponential doubles the
+/// delay after each attempt up to `max_delay` (0 for no cap), leaving out up to half of it at
+/// random. `max_attempts` caps the attempts, 0 for no cap. Once reconnected, the session is sent
+/// again and a player who was logged in is logged in again, with their login token or else
+/// their password, restoring their room, variables and syncs. The login callback tells how
+/// that went.
+function crystal_set_reconnect_policy(strategy, delay = 1000, max_delay = 30000, max_attempts = 0) {
+    return __crystal_set_reconnect_policy(strategy, delay, max_delay, max_attempts);
+}
+
 function crystal_update() {
 	var r = __crystal_update();
+    __crystal_update_room();
+    __crystal_drain(undefined);
+    return r;
+}
+
+/// Like crystal_update(), but takes about `budget` microseconds at most, to cap the cost of
+/// networking per frame. Returns whether work is still pending, which the next call continues.
+function crystal_update_budget(budget) {
+    var deadline = get_timer() + budget;
+    var pending = __crystal_update_budget(budget);
+    __crystal_update_room();
+    return __crystal_drain(deadline) || pending;
+}
+
+function __crystal_update_room() {
     var rm = string(room);
     if global.__crystal_callback_room != undefined
         rm = string(global.__crystal_callback_room());
     __crystal_set_room(rm);
-    var notf = __crystal_get_notification();
-    while string_length(notf) > 0 {
-        var s = string_split(notf, ";");
-		//show_debug_message(notf + "\t\t\t" + string(s));
-        switch s[0] {
-			case "login_token":
-				if global.__crystal_callback_login_token != undefined
-					global.__crystal_callback_login_token(base64_decode(s[1]));
-				break;
-            case "admin_action":
-                switch s[1] {
-                    case "1":
-                        if global.__crystal_callback_banned != undefined
-                            global.__crystal_callback_banned(base64_decode(s[2]), int64(s[3]));
-                        break;
-                    case "2":
-                        if global.__crystal_callback_kicked != undefined
-                            global.__crystal_callback_kicked(base64_decode(s[2]));
-                        break;
-                }
-                break;
-            case "banned":
-                /*if global.__crystal_callback_banned != undefined
-                    global.__crystal_callback_banned(base64_decode(s[1]), int64(s[2]));*/
-                break;
-            case "kicked":
-                /*if global.__crystal_callback_kicked != undefined
-                    global.__crystal_callback_kicked(base64_decode(s[1]));*/
-                break;
-            case "friend_status": // status->u64
-                break;
-            case "disconnected":
-                if global.__crystal_callback_disconnected != undefined
-                    global.__crystal_callback_disconnected();
-                break;
-            case "fetch_bdb":
-                switch s[1] {
-                    case "0":
-                        if global.__crystal_callback_bdb != undefined
-                            global.__crystal_callback_bdb(base64_decode(s[2]));
-                        break;
-                    case "1":
-                        if global.__crystal_callback_bdb != undefined
-                            global.__crystal_callback_bdb(base64_decode(s[2]), base64_decode(s[3]));
-                        break;
-                }
-                break;
-            case "login":
-                if global.__crystal_callback_login != undefined
-                    global.__crystal_callback_login(real(s[1]));
-                break;
-            case "login_ok":
-                if global.__crystal_callback_login != undefined
-                    global.__crystal_callback_login(LoginResult.OK);
-				break;
-			case "login_ban":
-                if global.__crystal_callback_login != undefined
-                    global.__crystal_callback_login(real(s[1]), base64_decode(s[2]), int64(s[3]));
-                break;
-            case "p2p":
-                if global.__crystal_callback_p2p != undefined {
-                    var _pid = -1;
-                    if s[1] != "!"
-                        _pid = real(s[1]);
-                    global.__crystal_callback_p2p(_pid, real(s[2]), __decode_variable(s[3]));
-                }
-                break;
-            case "register":
-                if global.__crystal_callback_register != undefined
-                    global.__crystal_callback_register(real(s[1]));
-                break;
-            case "player_logged_in": // pid->u64,name->string_base64,room->string_base64
-                break;
-            case "player_logged_out": // pid->u64
-                break;
-            case "reconnecting":
-                break;
-            case "server_message": // server_message->string_base64
-                break;
-            case "update_variable":
-                if global.__crystal_callback_update_variable != undefined
-                    global.__crystal_callback_update_variable(real(s[1]), base64_decode(s[2]), __decode_variable(s[3]), s[3] == "!!");
-                break;
-            case "update_sync_variable":
-                if global.__crystal_callback_update_sync_variable != undefined
-                    global.__crystal_callback_update_sync_variable(real(s[1]), real(s[2]), base64_decode(s[3]), __decode_variable(s[4]), s[4] == "!!");
-                break;
-            case "update_sync_removal": // pid->u64,slot->u64
-                break;
-            case "update_gameini": // file->string_base64,section->string_base64,key->string_base64,value->vari
+}
+
+/// Handles queued notifications until get_timer() reaches `deadline`, or all of them if it's
+/// undefined. Returns whether some are left.
+function __crystal_drain(deadline) {
+    if global.__crystal_drain_buffer == undefined
+        global.__crystal_drain_buffer = buffer_create(65536, buffer_fixed, 1);
+    var b = global.__crystal_drain_buffer;
+    // Records a previous call drained but ran out of time for come first.
+    if __crystal_handle_drained(b, deadline)
+        return true;
+    // Only what's queued now, so a steady stream of notifications can't keep us here.
+    var pending = __crystal_get_notification_count();
+    while pending > 0 {
+        if deadline != undefined && get_timer() >= deadline
+            return true;
+        var count = __crystal_drain_notifications_binary(buffer_get_address(b), buffer_get_size(b));
+        if count == 0 {
+            if __crystal_get_notification_count() == 0
                 break;
-            case "update_playerini": // file->string_base64,section->string_base64,key->string_base64,value->vari
+            // The next notification doesn't fit.
+            buffer_resize(b, buffer_get_size(b) * 2);
+            continue;
+        }
+        pending -= count;
+        buffer_seek(b, buffer_seek_start, 0);
+        global.__crystal_drain_left = count;
+        if __crystal_handle_drained(b, deadline)
+            return true;
+    }
+    return __crystal_get_notification_count() > 0;
+}
+
+/// Handles the records left in `b`, returns whether `deadline` came first.
+function __crystal_handle_drained(b, deadline) {
+    while global.__crystal_drain_left > 0 {
+        if deadline != undefined && get_timer() >= deadline
+            return true;
+        global.__crystal_drain_left--;
+        __crystal_handle_notification(__crystal_read_notification(b));
+    }
+    return false;
+}
+
+/// Delivers notifications through the Async - Social event instead of crystal_update(),
+/// call crystal_async_social() from that event once this returns true.
+/// Returns false when the runner never registered its callbacks, polling stays in use then.
+function crystal_set_async_events(enabled) {
+    return __crystal_set_async_events(enabled);
+}
+
+/// Caps how many notifications wait for crystal_update(), 0 for no cap.
+/// `policy` is a NotificationOverflow deciding what gets dropped once the cap is reached.
+function crystal_set_notification_limit(limit, policy = NotificationOverflow.DropOldest) {
+    return __crystal_set_notification_limit(limit, policy);
+}
+
+/// Returns a struct mapping each notification kind to how many of them were dropped.
+function crystal_get_dropped_notifications() {
+    var res = {};
+    var s = __crystal_get_dropped_notifications();
+    if s == ""
+        return res;
+    s = string_split(s, ";");
+    for (var i = 0; i < array_length(s); i++) {
+        var _pair = string_split(s[i], ":");
+        res[$ _pair[0]] = real(_pair[1]);
+    }
+    return res;
+}
+
+function crystal_reset_dropped_notifications() {
+    return __crystal_reset_dropped_notifications();
+}
+
+/// Only delivers notifications of the given kinds (e.g. ["p2p", "update_variable"]),
+/// an empty array delivers all of them. Tickets are always delivered.
+/// Returns the unknown kinds, nothing changes unless that's empty.
+function crystal_set_subscriptions(kinds) {
+    var s = "";
+    for (var i = 0; i < array_length(kinds); i++)
+        s += (i > 0 ? ";" : "") + kinds[i];
+    var unknown = __crystal_set_subscriptions(s);
+    return unknown == "" ? [] : string_split(unknown, ";");
+}
+
+/// Returns the kinds of notifications that are currently delivered.
+function crystal_get_subscriptions() {
+    return string_split(__crystal_get_subscriptions(), ";");
+}
+
+/// While enabled, only the latest pending value of each player and sync variable is delivered
+/// by crystal_update(), removals still arrive in order.
+function crystal_set_coalescing(enabled) {
+    return __crystal_set_coalescing(enabled);
+}
+
+/// Sends integers and floats, both ways, as their bits instead of their text, so that int64 values
+/// aren't rounded through reals and floats (including NaN, infinity and -0) come back exactly.
+/// Switches values to format 2 when enabled, format 1 has no lossless form.
+function crystal_set_lossless_values(enabled) {
+    global.__crystal_lossless_values = enabled;
+    if enabled
+        __crystal_set_value_format(2);
+    return __crystal_set_lossless_values(enabled);
+}
+
+/// Sorts the members of structs and the variables of players and syncs by name, so that equal
+/// values always come encoded the same.
+function crystal_set_canonical_values(enabled) {
+    return __crystal_set_canonical_values(enabled);
+}
+
+/// Picks how the values the client returns are encoded: 1 (the default) or 2, which is
+/// flat and length-prefixed so it's quicker to decode. Either is decoded the same by these
+/// functions, only callers of the __crystal_* exports see the difference.
+function crystal_set_value_format(version) {
+    return __crystal_set_value_format(version);
+}
+
+/// Has the exports returning players, syncs, achievements, highscores, administrators or
+/// friends return JSON instead of their own layouts, which is quicker to decode. These
+/// functions return the same either way, except that achievements and highscores come as
+/// structs instead of their raw layout.
+function crystal_set_json_output(enabled) {
+    global.__crystal_json_output = enabled;
+    return __crystal_set_json_output(enabled);
+}
+
+/// Call from the Async - Social event, returns whether async_load was a crystal notification.
+/// Notifications of clients made with crystal_client_create() are left to the game, their
+/// async_load holds the client's handle.
+function crystal_async_social() {
+    if async_load[? "type"] != "crystal" || async_load[? "handle"] != 0
+        return false;
+    var n = {};
+    var keys = ds_map_keys_to_array(async_load);
+    for (var i = 0; i < array_length(keys); i++)
+        n[$ keys[i]] = async_load[? keys[i]];
+    if variable_struct_exists(n, "payload")
+        n.payload = __decode_variable(n.payload);
+    if variable_struct_exists(n, "value") && n.kind != "fetch_bdb"
+        n.value = __decode_variable(n.value);
+    __crystal_handle_notification(n);
+    return true;
+}
+
+/// Turns a polled notification into the same struct crystal_async_social() builds from async_load,
+/// with values already decoded.
+function __crystal_parse_notification(notf) {
+    // Values are always last and may contain ";" in version 2 of their encoding, so they're
+    // decoded from the rest of the fields joined back.
+    var s = string_split(notf, ";");
+    var n = { seq: real(s[0]), kind: s[1] };
+    array_delete(s, 0, 1);
+    switch s[0] {
+        case "login_token":
+            n.token = base64_decode(s[1]);
+            break;
+        case "admin_action":
+            n.action = real(s[1]);
+            if n.action != 0
+                n.reason = base64_decode(s[2]);
+            if n.action == 1
+                n.unban_time = int64(s[3]);
+            break;
+        case "banned":
+            n.reason = base64_decode(s[1]);
+            n.unban_time = int64(s[2]);
+            break;
+        case "kicked":
+        case "server_message":
+        case "server_notification":
+            if s[0] == "kicked"
+                n.reason = base64_decode(s[1]);
+            else
+                n.message = base64_decode(s[1]);
+            break;
+        case "friend_status":
+            n.status = real(s[1]);
+            break;
+        case "fetch_bdb":
+            n.name = base64_decode(s[2]);
+            if s[1] == "1"
+                n.value = s[3];
+            break;
+        case "login":
+        case "register":
+            n.code = real(s[1]);
+            break;
+        case "login_ok":
+            n.pid = real(s[1]);
+            n.name = base64_decode(s[2]);
+            break;
+        case "login_ban":
+            n.code = real(s[1]);
+            n.reason = base64_decode(s[2]);
+            n.unban_time = int64(s[3]);
+            break;
+        case "p2p":
+            n.sender = s[1] == "!" ? -1 : real(s[1]);
+            n.message_id = real(s[2]);
+            n.payload = __decode_variable(string_join_ext(";", s, 3));
+            break;
+        case "player_logged_in":
+            n.pid = real(s[1]);
+            n.name = base64_decode(s[2]);
+            n.room = base64_decode(s[3]);
+            break;
+        case "player_logged_out":
+            n.pid = real(s[1]);
+            break;
+        case "update_variable":
+            n.pid = real(s[1]);
+            n.name = base64_decode(s[2]);
+            n.value = __decode_variable(string_join_ext(";", s, 3));
+            n.removed = s[3] == "!!";
+            break;
+        case "update_sync_variable":
+            n.pid = real(s[1]);
+            n.slot = real(s[2]);
+            n.name = base64_decode(s[3]);
+            n.value = __decode_variable(string_join_ext(";", s, 4));
+            n.removed = s[4] == "!!";
+            break;
+        case "update_sync_removal":
+            n.pid = real(s[1]);
+            n.slot = real(s[2]);
+            break;
+        case "update_gameini":
+        case "update_playerini":
+            if s[1] != "!!"
+                n.file = base64_decode(s[1]);
+            n.section = base64_decode(s[2]);
+            n.key = base64_decode(s[3]);
+            n.value = __decode_variable(string_join_ext(";", s, 4));
+            n.removed = s[4] == "!!";
+            break;
+        case "update_gameversion":
+            n.version = real(s[1]);
+            break;
+        case "update_administrator":
+            n.pid = real(s[1]);
+            if s[2] != "!" {
+                var _admin = string_split(s[2], ":");
+                n.can_ban = _admin[0] == "true";
+                n.can_unban = _admin[1] == "true";
+                n.can_kick = _admin[2] == "true";
+            }
+            break;
+        case "player_variable_request":
+        case "sync_variable_request":
+            n.request = real(s[1]);
+            n.pid = real(s[2]);
+            n.name = s[3];
+            n.value = __decode_variable(string_join_ext(";", s, 4));
+            break;
+        case "ticket":
+            n.ticket = real(s[1]);
+            n.ok = s[2] == "1";
+            n.result = base64_decode(s[3]);
+            break;
+        case "connect_result":
+            n.ok = s[1] == "1";
+            n.category = n.ok ? "" : s[2];
+            n.message = n.ok ? "" : base64_decode(s[3]);
+            break;
+        case "reconnecting":
+            n.attempt = real(s[1]);
+            n.delay = real(s[2]);
+            break;
+        case "reconnect_failed":
+            n.attempts = real(s[1]);
+            break;
+    }
+    return n;
+}
+
+/// Reads a record written by __crystal_drain_notifications_binary into the same struct as
+/// __crystal_parse_notification().
+function __crystal_read_notification(b) {
+    buffer_read(b, buffer_u32);
+    var n = { seq: buffer_read(b, buffer_u64) };
+    n.kind = __crystal_read_string(b);
+    repeat buffer_read(b, buffer_u8) {
+        var _name = __crystal_read_string(b);
+        switch buffer_read(b, buffer_u8) {
+            case 0:
+                n[$ _name] = buffer_read(b, buffer_f64);
                 break;
-            case "update_gameversion": // version->f64
+            case 1:
+                n[$ _name] = __crystal_read_string(b);
                 break;
-            case "update_administrator": // pid->u64,admin->admin
+            case 2:
+                // Bytes stay base64, as in the other notification formats.
+                var _len = buffer_read(b, buffer_u32);
+                n[$ _name] = _len > 0 ? buffer_base64_encode(b, buffer_tell(b), _len) : "";
+                buffer_seek(b, buffer_seek_relative, _len);
                 break;
-            case "server_notification": // notif->string_base64
+            case 3:
+                n[$ _name] = __crystal_read_value(b);
                 break;
         }
-        notf = __crystal_get_notification();
     }
-    return r;
+    return n;
+}
+
+function __crystal_read_string(b) {
+    buffer_read(b, buffer_u32);
+    return buffer_read(b, buffer_string);
+}
+
+function __crystal_read_value(b) {
+    switch buffer_read(b, buffer_u8) {
+        case 0:
+            // Read as unsigned, but int64 shares the bit pattern.
+            return buffer_read(b, buffer_u64);
+        case 1:
+            return buffer_read(b, buffer_f64);
+        case 2:
+            return buffer_read(b, buffer_u8) != 0;
+        case 3:
+            return __crystal_read_string(b);
+        case 4:
+            var _len = buffer_read(b, buffer_u32);
+            var r = buffer_create(max(_len, 1), buffer_fixed, 1);
+            if _len > 0
+                buffer_copy(b, buffer_tell(b), _len, r, 0);
+            buffer_seek(b, buffer_seek_relative, _len);
+            return r;
+        case 5:
+            r = array_create(buffer_read(b, buffer_u32));
+            for (var i = 0; i < array_length(r); i++)
+                r[i] = __crystal_read_value(b);
+            return r;
+        case 6:
+            r = {};
+            repeat buffer_read(b, buffer_u32) {
+                var _name = __crystal_read_string(b);
+                r[$ _name] = __crystal_read_value(b);
+            }
+            return r;
+        default:
+            return undefined;
+    }
+}
+
+function __crystal_handle_notification(n) {
+    if n.seq > global.__crystal_last_seq + 1 && global.__crystal_callback_missed_notifications != undefined
+        global.__crystal_callback_missed_notifications(global.__crystal_last_seq + 1, n.seq - global.__crystal_last_seq - 1);
+    global.__crystal_last_seq = n.seq;
+    switch n.kind {
+        case "login_token":
+            if global.__crystal_callback_login_token != undefined
+                global.__crystal_callback_login_token(n.token);
+            break;
+        case "admin_action":
+            switch n.action {
+                case 1:
+                    if global.__crystal_callback_banned != undefined
+                        global.__crystal_callback_banned(n.reason, n.unban_time);
+                    break;
+                case 2:
+                    if global.__crystal_callback_kicked != undefined
+                        global.__crystal_callback_kicked(n.reason);
+                    break;
+            }
+            break;
+        case "banned":
+            /*if global.__crystal_callback_banned != undefined
+                global.__crystal_callback_banned(n.reason, n.unban_time);*/
+            break;
+        case "kicked":
+            /*if global.__crystal_callback_kicked != undefined
+                global.__crystal_callback_kicked(n.reason);*/
+            break;
+        case "friend_status": // status
+            break;
+        case "disconnected":
+            if global.__crystal_callback_disconnected != undefined
+                global.__crystal_callback_disconnected();
+            break;
+        case "fetch_bdb":
+            if global.__crystal_callback_bdb != undefined {
+                if variable_struct_exists(n, "value")
+                    global.__crystal_callback_bdb(n.name, base64_decode(n.value));
+                else
+                    global.__crystal_callback_bdb(n.name);
+            }
+            break;
+        case "login":
+            if global.__crystal_callback_login != undefined
+                global.__crystal_callback_login(n.code);
+            break;
+        case "login_ok":
+            if global.__crystal_callback_login != undefined
+                global.__crystal_callback_login(LoginResult.OK);
+            break;
+        case "login_ban":
+            if global.__crystal_callback_login != undefined
+                global.__crystal_callback_login(n.code, n.reason, n.unban_time);
+            break;
+        case "p2p":
+            if global.__crystal_callback_p2p != undefined
+                global.__crystal_callback_p2p(n.sender, n.message_id, n.payload);
+            break;
+        case "register":
+            if global.__crystal_callback_register != undefined
+                global.__crystal_callback_register(n.code);
+            break;
+        case "player_logged_in": // pid,name,room
+            break;
+        case "player_logged_out": // pid
+            break;
+        case "reconnecting":
+            if global.__crystal_callback_reconnecting != undefined
+                global.__crystal_callback_reconnecting(n.attempt, n.delay);
+            break;
+        case "server_message": // message
+            break;
+        case "update_variable":
+            if global.__crystal_callback_update_variable != undefined
+                global.__crystal_callback_update_variable(n.pid, n.name, n.value, n.removed);
+            break;
+        case "update_sync_variable":
+            if global.__crystal_callback_update_sync_variable != undefined
+                global.__crystal_callback_update_sync_variable(n.pid, n.slot, n.name, n.value, n.removed);
+            break;
+        case "update_sync_removal": // pid,slot
+            break;
+        case "update_gameini": // file?,section,key,value,removed
+            break;
+        case "update_playerini": // file?,section,key,value,removed
+            break;
+        case "update_gameversion": // version
+            break;
+        case "update_administrator": // pid,can_ban?,can_unban?,can_kick?
+            break;
+        case "server_notification": // message
+            break;
+        case "ticket":
+            var _key = string(n.ticket);
+            var _t = global.__crystal_tickets[$ _key];
+            if _t != undefined {
+                variable_struct_remove(global.__crystal_tickets, _key);
+                var _res = n.result;
+                if n.ok && _t[1] != undefined
+                    _res = _t[1](_res);
+                _t[0](n.ok, _res);
+            }
+            break;
+        case "connect_result":
+            if global.__crystal_callback_connect_result != undefined
+                global.__crystal_callback_connect_result(n.ok, n.category, n.message);
+            break;
+        case "reconnect_failed":
+            if global.__crystal_callback_reconnect_failed != undefined
+                global.__crystal_callback_reconnect_failed(n.attempts);
+            break;
+    }
 }
 
 function crystal_is_connected() {
@@ -314,21 +795,32 @@ function crystal_set_variable(name, variable) {
     return __crystal_set_variable(name, __encode_variable(variable));
 }
 
+/// Returns and clears the reason the last value sent to the server couldn't be decoded,
+/// or "" if none failed. The call which sent that value was skipped.
+function crystal_get_last_decode_error() {
+    return __crystal_get_last_decode_error();
+}
+
+/// Converts JSON into a value as sent by crystal, e.g. to tell integers from floats,
+/// or returns undefined if it isn't valid JSON (see crystal_get_last_decode_error()).
+/// __crystal_set_variable, __crystal_set_playerini, __crystal_p2p and the likes also directly
+/// accept a json_stringify() of a struct or an array in place of an encoded value.
+function crystal_json_to_value(json) {
+    var s = __crystal_json_to_value(json);
+    return s == "" ? undefined : __decode_variable(s);
+}
+
+/// Converts a value into JSON, buffers become base64 strings.
+function crystal_value_to_json(value) {
+    return __crystal_value_to_json(__encode_variable(value));
+}
+
 function crystal_remove_variable(name) {
     return __crystal_remove_variable(name);
 }
 
 function crystal_iter_other_players() {
-	var ss = __crystal_iter_other_players();
-	//show_debug_message(ss);
-    var s = string_split(ss, ";");
-	//show_debug_message(r + "\t\t\t" + string(s));
-    var r = [];
-	if array_length(s) == 1 && string_length(s[0]) == 0
-		return r;
-    for (var i = 0; i < array_length(s); i++)
-        array_push(r, __decode_player(s[i]));
-    return r;
+	return __decode_player_list(__crystal_iter_other_players());
 }
 
 function crystal_other_player_count() {
@@ -348,10 +840,7 @@ function crystal_request_other_player_variable(pid, name, request) {
 }
 
 function crystal_p2p(target, mid, payload) {
-    var s = string(array_length(payload));
-    for (var i = 0; i < array_length(payload); i++)
-        s += ";" + __encode_variable(payload[i]);
-    return __crystal_p2p(target, mid, s);
+    return __crystal_p2p(target, mid, __encode_payload(payload));
 }
 
 function crystal_set_version(version) {
@@ -435,7 +924,7 @@ function crystal_has_achievement(aid) {
 }
 
 function crystal_get_achievement(aid) {
-    return __crystal_get_achievement(aid);
+    return __decode_as_is(__crystal_get_achievement(aid));
 }
 
 function crystal_has_reached_achievement(aid) {
@@ -455,7 +944,7 @@ function crystal_has_highscore(hid) {
 }
 
 function crystal_get_highscore(hid) {
-    return __crystal_get_highscore(hid);
+    return __decode_as_is(__crystal_get_highscore(hid));
 }
 
 function crystal_has_score_highscore(hid) {
@@ -491,16 +980,7 @@ function crystal_get_variable_other_sync(pid, sync, name) {
 }
 
 function crystal_iter_other_syncs() {
-    var ss = __crystal_iter_other_syncs();
-	//show_debug_message(ss);
-    var s = string_split(ss, ";");
-	//show_debug_message(ss + "\t\t\t" + string(s));
-    var r = [];
-	if array_length(s) == 1 && string_length(s[0]) == 0
-		return r;
-    for (var i = 0; i < array_length(s); i++)
-        array_push(r, __decode_synciter(s[i]));
-    return r;
+    return __decode_synciter_list(__crystal_iter_other_syncs());
 }
 
 function crystal_is_player_admin(pid) {
@@ -540,30 +1020,15 @@ function crystal_set_bdb(name, data) {
 }
 
 function crystal_get_incoming_friends() {
-    var s = string_split(__crystal_get_incoming_friends(), ":");
-    var r = [];
-    var sz = real(s[0]);
-    for (var i = 0; i < sz; i++)
-        array_push(r, real(s[i + 1]));
-    return r;
+    return __decode_friends(__crystal_get_incoming_friends());
 }
 
 function crystal_get_outgoing_friends() {
-    var s = string_split(__crystal_get_outgoing_friends(), ":");
-    var r = [];
-    var sz = real(s[0]);
-    for (var i = 0; i < sz; i++)
-        array_push(r, real(s[i + 1]));
-    return r;
+    return __decode_friends(__crystal_get_outgoing_friends());
 }
 
 function crystal_get_friends() {
-    var s = string_split(__crystal_get_friends(), ":");
-    var r = [];
-    var sz = real(s[0]);
-    for (var i = 0; i < sz; i++)
-        array_push(r, real(s[i + 1]));
-    return r;
+    return __decode_friends(__crystal_get_friends());
 }
 
 function crystal_send_outgoing_friend(pid) {
@@ -586,13 +1051,375 @@ function crystal_remove_friend(pid) {
     return __crystal_remove_friend(pid);
 }
 
+/// Registers `callback(ok, result)` to be called once `ticket` completes.
+/// On success `result` is passed through `decoder` (if any), on failure it's the error message.
+function __crystal_ticket(ticket, callback, decoder = undefined) {
+    if callback != undefined
+        global.__crystal_tickets[$ string(ticket)] = [callback, decoder];
+    return ticket;
+}
+
+function crystal_update_async(callback = undefined) {
+    return __crystal_ticket(__crystal_update_async(), callback);
+}
+
+function crystal_is_connected_async(callback = undefined) {
+    return __crystal_ticket(__crystal_is_connected_async(), callback, __decode_bool);
+}
+
+function crystal_is_connecting_async(callback = undefined) {
+    return __crystal_ticket(__crystal_is_connecting_async(), callback, __decode_bool);
+}
+
+function crystal_is_loggedin_async(callback = undefined) {
+    return __crystal_ticket(__crystal_is_loggedin_async(), callback, __decode_bool);
+}
+
+function crystal_get_ping_async(callback = undefined) {
+    return __crystal_ticket(__crystal_get_ping_async(), callback, __decode_real);
+}
+
+function crystal_set_game_token_async(token, callback = undefined) {
+    return __crystal_ticket(__crystal_set_game_token_async(token), callback);
+}
+
+function crystal_disconnect_async(callback = undefined) {
+    return __crystal_ticket(__crystal_disconnect_async(), callback);
+}
+
+function crystal_login_async(name, passw, callback = undefined) {
+    return __crystal_ticket(__crystal_login_async(name, passw), callback);
+}
+
+function crystal_login_with_token_async(name, token, callback = undefined) {
+    return __crystal_ticket(__crystal_login_with_token_async(name, token), callback);
+}
+
+function crystal_register_async(name, email, passw, repeat_passw, callback = undefined) {
+    return __crystal_ticket(__crystal_register_async(name, email, passw, repeat_passw), callback);
+}
+
+function crystal_get_player_id_async(callback = undefined) {
+    return __crystal_ticket(__crystal_get_player_id_async(), callback, __decode_real);
+}
+
+function crystal_get_player_name_async(callback = undefined) {
+    return __crystal_ticket(__crystal_get_player_name_async(), callback);
+}
+
+function crystal_set_variable_async(name, variable, callback = undefined) {
+    return __crystal_ticket(__crystal_set_variable_async(name, __encode_variable(variable)), callback);
+}
+
+function crystal_remove_variable_async(name, callback = undefined) {
+    return __crystal_ticket(__crystal_remove_variable_async(name), callback);
+}
+
+function crystal_iter_other_players_async(callback = undefined) {
+    return __crystal_ticket(__crystal_iter_other_players_async(), callback, __decode_player_list);
+}
+
+function crystal_other_player_count_async(callback = undefined) {
+    return __crystal_ticket(__crystal_other_player_count_async(), callback, __decode_real);
+}
+
+function crystal_get_other_player_async(pid, callback = undefined) {
+    return __crystal_ticket(__crystal_get_other_player_async(pid), callback, __decode_player);
+}
+
+function crystal_get_other_player_name_async(name, callback = undefined) {
+    return __crystal_ticket(__crystal_get_other_player_name_async(name), callback, __decode_player);
+}
+
+function crystal_request_other_player_variable_async(pid, name, request, callback = undefined) {
+    return __crystal_ticket(__crystal_request_other_player_variable_async(pid, name, request), callback);
+}
+
+function crystal_p2p_async(target, mid, payload, callback = undefined) {
+    return __crystal_ticket(__crystal_p2p_async(target, mid, __encode_payload(payload)), callback);
+}
+
+function crystal_set_version_async(version, callback = undefined) {
+    return __crystal_ticket(__crystal_set_version_async(version), callback);
+}
+
+function crystal_get_version_async(callback = undefined) {
+    return __crystal_ticket(__crystal_get_version_async(), callback, __decode_real);
+}
+
+function crystal_get_server_version_async(callback = undefined) {
+    return __crystal_ticket(__crystal_get_server_version_async(), callback, __decode_real);
+}
+
+function crystal_set_session_async(session, callback = undefined) {
+    return __crystal_ticket(__crystal_set_session_async(session), callback);
+}
+
+function crystal_get_session_async(callback = undefined) {
+    return __crystal_ticket(__crystal_get_session_async(), callback);
+}
+
+function crystal_get_open_playerini_async(callback = undefined) {
+    return __crystal_ticket(__crystal_get_open_playerini_async(), callback);
+}
+
+function crystal_open_playerini_async(file, callback = undefined) {
+    return __crystal_ticket(__crystal_open_playerini_async(file), callback);
+}
+
+function crystal_close_playerini_async(callback = undefined) {
+    return __crystal_ticket(__crystal_close_playerini_async(), callback);
+}
+
+function crystal_has_playerini_async(section, key, callback = undefined) {
+    return __crystal_ticket(__crystal_has_playerini_async(section, key), callback, __decode_bool);
+}
+
+function crystal_get_playerini_async(section, key, callback = undefined) {
+    return __crystal_ticket(__crystal_get_playerini_async(section, key), callback, __decode_variable);
+}
+
+function crystal_set_playerini_async(section, key, value, callback = undefined) {
+    return __crystal_ticket(__crystal_set_playerini_async(section, key, __encode_variable(value)), callback);
+}
+
+function crystal_remove_playerini_async(section, key, callback = undefined) {
+    return __crystal_ticket(__crystal_remove_playerini_async(section, key), callback);
+}
+
+function crystal_get_open_gameini_async(callback = undefined) {
+    return __crystal_ticket(__crystal_get_open_gameini_async(), callback);
+}
+
+function crystal_open_gameini_async(file, callback = undefined) {
+    return __crystal_ticket(__crystal_open_gameini_async(file), callback);
+}
+
+function crystal_close_gameini_async(callback = undefined) {
+    return __crystal_ticket(__crystal_close_gameini_async(), callback);
+}
+
+function crystal_has_gameini_async(section, key, callback = undefined) {
+    return __crystal_ticket(__crystal_has_gameini_async(section, key), callback, __decode_bool);
+}
+
+function crystal_get_gameini_async(section, key, callback = undefined) {
+    return __crystal_ticket(__crystal_get_gameini_async(section, key), callback, __decode_variable);
+}
+
+function crystal_set_gameini_async(section, key, value, callback = undefined) {
+    return __crystal_ticket(__crystal_set_gameini_async(section, key, __encode_variable(value)), callback);
+}
+
+function crystal_remove_gameini_async(section, key, callback = undefined) {
+    return __crystal_ticket(__crystal_remove_gameini_async(section, key), callback);
+}
+
+function crystal_has_achievement_async(aid, callback = undefined) {
+    return __crystal_ticket(__crystal_has_achievement_async(aid), callback, __decode_bool);
+}
+
+function crystal_get_achievement_async(aid, callback = undefined) {
+    return __crystal_ticket(__crystal_get_achievement_async(aid), callback, __decode_as_is);
+}
+
+function crystal_has_reached_achievement_async(aid, callback = undefined) {
+    return __crystal_ticket(__crystal_has_reached_achievement_async(aid), callback, __decode_bool);
+}
+
+function crystal_get_reached_achievement_async(aid, callback = undefined) {
+    return __crystal_ticket(__crystal_get_reached_achievement_async(aid), callback, __decode_real);
+}
+
+function crystal_reach_achievement_async(aid, callback = undefined) {
+    return __crystal_ticket(__crystal_reach_achievement_async(aid), callback);
+}
+
+function crystal_has_highscore_async(hid, callback = undefined) {
+    return __crystal_ticket(__crystal_has_highscore_async(hid), callback, __decode_bool);
+}
+
+function crystal_get_highscore_async(hid, callback = undefined) {
+    return __crystal_ticket(__crystal_get_highscore_async(hid), callback, __decode_as_is);
+}
+
+function crystal_has_score_highscore_async(hid, callback = undefined) {
+    return __crystal_ticket(__crystal_has_score_highscore_async(hid), callback, __decode_bool);
+}
+
+function crystal_get_score_highscore_async(hid, callback = undefined) {
+    return __crystal_ticket(__crystal_get_score_highscore_async(hid), callback, __decode_real);
+}
+
+function crystal_set_score_highscore_async(hid, score, callback = undefined) {
+    return __crystal_ticket(__crystal_set_score_highscore_async(hid, score), callback);
+}
+
+function crystal_create_sync_async(sync_type, kind, callback = undefined) {
+    return __crystal_ticket(__crystal_create_sync_async(sync_type, kind), callback, __decode_real);
+}
+
+function crystal_destroy_sync_async(sync, callback = undefined) {
+    return __crystal_ticket(__crystal_destroy_sync_async(sync), callback);
+}
+
+function crystal_set_variable_sync_async(sync, name, value, callback = undefined) {
+    return __crystal_ticket(__crystal_set_variable_sync_async(sync, name, __encode_variable(value)), callback);
+}
+
+function crystal_remove_variable_sync_async(sync, name, callback = undefined) {
+    return __crystal_ticket(__crystal_remove_variable_sync_async(sync, name), callback);
+}
+
+function crystal_get_variable_other_sync_async(pid, sync, name, callback = undefined) {
+    return __crystal_ticket(__crystal_get_variable_other_sync_async(pid, sync, name), callback, __decode_variable);
+}
+
+function crystal_iter_other_syncs_async(callback = undefined) {
+    return __crystal_ticket(__crystal_iter_other_syncs_async(), callback, __decode_synciter_list);
+}
+
+function crystal_is_player_admin_async(pid, callback = undefined) {
+    return __crystal_ticket(__crystal_is_player_admin_async(pid), callback, __decode_bool);
+}
+
+function crystal_get_player_admin_async(pid, callback = undefined) {
+    return __crystal_ticket(__crystal_get_player_admin_async(pid), callback, __decode_administrator);
+}
+
+function crystal_player_kick_async(pid, reason, callback = undefined) {
+    return __crystal_ticket(__crystal_player_kick_async(pid, reason), callback, __decode_bool);
+}
+
+function crystal_player_ban_async(pid, reason, unban_time, callback = undefined) {
+    return __crystal_ticket(__crystal_player_ban_async(pid, reason, unban_time), callback, __decode_bool);
+}
+
+function crystal_player_unban_async(pid, callback = undefined) {
+    return __crystal_ticket(__crystal_player_unban_async(pid), callback, __decode_bool);
+}
+
+function crystal_logout_async(callback = undefined) {
+    return __crystal_ticket(__crystal_logout_async(), callback, __decode_bool);
+}
+
+function crystal_request_other_sync_variable_async(pid, slot, name, request, callback = undefined) {
+    return __crystal_ticket(__crystal_request_other_sync_variable_async(pid, slot, name, request), callback);
+}
+
+function crystal_fetch_bdb_async(name, callback = undefined) {
+    return __crystal_ticket(__crystal_fetch_bdb_async(name), callback);
+}
+
+function crystal_set_bdb_async(name, data, callback = undefined) {
+    return __crystal_ticket(__crystal_set_bdb_async(name, buffer_base64_encode(data, 0, buffer_tell(data))), callback);
+}
+
+function crystal_get_incoming_friends_async(callback = undefined) {
+    return __crystal_ticket(__crystal_get_incoming_friends_async(), callback, __decode_friends);
+}
+
+function crystal_get_outgoing_friends_async(callback = undefined) {
+    return __crystal_ticket(__crystal_get_outgoing_friends_async(), callback, __decode_friends);
+}
+
+function crystal_get_friends_async(callback = undefined) {
+    return __crystal_ticket(__crystal_get_friends_async(), callback, __decode_friends);
+}
+
+function crystal_send_outgoing_friend_async(pid, callback = undefined) {
+    return __crystal_ticket(__crystal_send_outgoing_friend_async(pid), callback);
+}
+
+function crystal_remove_outgoing_friend_async(pid, callback = undefined) {
+    return __crystal_ticket(__crystal_remove_outgoing_friend_async(pid), callback);
+}
+
+function crystal_deny_incoming_friend_async(pid, callback = undefined) {
+    return __crystal_ticket(__crystal_deny_incoming_friend_async(pid), callback);
+}
+
+function crystal_accept_incoming_friend_async(pid, callback = undefined) {
+    return __crystal_ticket(__crystal_accept_incoming_friend_async(pid), callback);
+}
+
+function crystal_remove_friend_async(pid, callback = undefined) {
+    return __crystal_ticket(__crystal_remove_friend_async(pid), callback);
+}
+
 /*
 function crystal_() {
     return __crystal_();
 }
 */
 
+function __decode_bool(s) {
+    return bool(real(s));
+}
+
+function __decode_real(s) {
+    return real(s);
+}
+
+/// Parses what structured exports return once crystal_set_json_output() switched them to JSON,
+/// with null turned into undefined.
+function __crystal_json_parse(s) {
+    return json_parse(s, function(_key, _value) {
+        return is_ptr(_value) && _value == pointer_null ? undefined : _value;
+    });
+}
+
+/// Returns achievements and highscores as they come, parsed in JSON output mode.
+function __decode_as_is(s) {
+    if global.__crystal_json_output
+        return __crystal_json_parse(s);
+    return s;
+}
+
+function __decode_friends(s) {
+    if global.__crystal_json_output
+        return __crystal_json_parse(s);
+    s = string_split(s, ":");
+    var r = [];
+    var sz = real(s[0]);
+    for (var i = 0; i < sz; i++)
+        array_push(r, real(s[i + 1]));
+    return r;
+}
+
+function __decode_player_list(ss) {
+    if global.__crystal_json_output
+        return __crystal_json_parse(ss);
+    var s = string_split(ss, ";");
+    var r = [];
+	if array_length(s) == 1 && string_length(s[0]) == 0
+		return r;
+    for (var i = 0; i < array_length(s); i++)
+        array_push(r, __decode_player(s[i]));
+    return r;
+}
+
+function __decode_synciter_list(ss) {
+    if global.__crystal_json_output
+        return __crystal_json_parse(ss);
+    var s = string_split(ss, ";");
+    var r = [];
+	if array_length(s) == 1 && string_length(s[0]) == 0
+		return r;
+    for (var i = 0; i < array_length(s); i++)
+        array_push(r, __decode_synciter(s[i]));
+    return r;
+}
+
+function __encode_payload(payload) {
+    return __encode_variable(payload);
+}
+
 function __decode_administrator(s) {
+    if global.__crystal_json_output
+        return __crystal_json_parse(s);
+    if s == "!"
+        return undefined;
     s = string_split(s, ":");
     var a = new CrystalAdministrator();
     a.can_ban = bool(real(s[0]));
@@ -603,6 +1430,8 @@ function __decode_administrator(s) {
 }
 
 function __decode_player(s) {
+    if global.__crystal_json_output
+        return __crystal_json_parse(s);
 	//show_debug_message(s);
     s = string_split(s, ":");
 	//show_debug_message(s);
@@ -648,33 +1477,45 @@ function __decode_sync(s) {
 }
 
 function __encode_variable(vari) {
+    return "v2:" + __crystal_encode_value(vari);
+}
+
+function __crystal_encode_value(vari) {
     switch typeof(vari) {
         case "undefined":
         case "null":
-            return "!";
+            return "n";
         case "int32":
         case "int64":
-            return "0:" + string(vari);
+            if global.__crystal_lossless_values
+                return "I" + __crystal_encode_bits(int64(vari));
+            return "i" + string(vari) + ";";
         case "number":
-            return "1:" + string(vari);
+            if global.__crystal_lossless_values {
+                buffer_poke(global.__crystal_bits_buffer, 0, buffer_f64, vari);
+                return "F" + __crystal_encode_bits(buffer_peek(global.__crystal_bits_buffer, 0, buffer_u64));
+            }
+            return "f" + string(vari) + ";";
         case "bool":
-            return "2:" + string(vari ? 1 : 0);
+            return vari ? "b1" : "b0";
         case "string":
-            return "3:" + base64_encode(vari);
+            return "s" + string(string_byte_length(vari)) + ":" + vari;
         case "ref":
-            if buffer_exists(vari)
-                return buffer_base64_encode(vari, 0, buffer_tell(vari));
+            if buffer_exists(vari) {
+                var e = buffer_base64_encode(vari, 0, buffer_tell(vari));
+                return "x" + string(string_length(e)) + ":" + e;
+            }
             show_error("Invalid variable type: " + typeof(vari) + " (" + string(vari) + ")", true);
         case "array":
-            var s = "5:" + string(array_length(vari));
+            var s = "a" + string(array_length(vari)) + ":";
             for (var i = 0; i < array_length(vari); i++)
-                s += ":" + base64_encode(__encode_variable(array_get(vari, i)));
+                s += __crystal_encode_value(vari[i]);
             return s;
         case "struct":
-            s = "6:" + string(variable_struct_names_count(vari));
             var v = variable_struct_get_names(vari);
+            s = "o" + string(array_length(v)) + ":";
             for (var i = 0; i < array_length(v); i++)
-                s += ":" + base64_encode(__encode_variable(variable_struct_get(vari, v[i])));
+                s += "s" + string(string_byte_length(v[i])) + ":" + v[i] + __crystal_encode_value(vari[$ v[i]]);
             return s;
         default:
             show_error("Invalid variable type: " + typeof(vari), true);
@@ -683,6 +1524,14 @@ function __encode_variable(vari) {
 
 function __decode_variable(ss) {
 	//show_debug_message(ss);
+    if string_starts_with(ss, "v2:") {
+        var b = buffer_create(string_byte_length(ss) + 1, buffer_fixed, 1);
+        buffer_write(b, buffer_string, ss);
+        buffer_seek(b, buffer_seek_start, 3);
+        var r = __crystal_decode_value(b);
+        buffer_delete(b);
+        return r;
+    }
     var s = string_split(ss, ":");
     switch s[0] {
         case "!":
@@ -707,8 +1556,83 @@ function __decode_variable(ss) {
         case "6":
             r = {};
             sz = real(s[1]);
-            for (var i = 0; i < sz; i += 2)
-                r[$ base64_decode(s[i + 2])] = __decode_variable(base64_decode(s[i + 3]));
+            for (var i = 0; i < sz; i++)
+                r[$ base64_decode(s[i * 2 + 2])] = __decode_variable(base64_decode(s[i * 2 + 3]));
+            return r;
+    }
+}
+
+function __crystal_encode_bits(bits) {
+    var s = "";
+    for (var i = 60; i >= 0; i -= 4)
+        s += string_char_at("0123456789abcdef", ((bits >> i) & 15) + 1);
+    return s;
+}
+
+function __crystal_decode_bits(b) {
+    var r = int64(0);
+    repeat 16 {
+        var c = buffer_read(b, buffer_u8);
+        r = (r << 4) | (c <= ord("9") ? c - ord("0") : (c | 32) - ord("a") + 10);
+    }
+    return r;
+}
+
+function __crystal_decode_token(b, terminator) {
+    var s = "";
+    var c = buffer_read(b, buffer_u8);
+    while c != terminator && c != 0 {
+        s += chr(c);
+        c = buffer_read(b, buffer_u8);
+    }
+    return s;
+}
+
+function __crystal_decode_field(b) {
+    var n = real(__crystal_decode_token(b, ord(":")));
+    var p = buffer_tell(b);
+    var c = buffer_peek(b, p + n, buffer_u8);
+    buffer_poke(b, p + n, buffer_u8, 0);
+    var s = buffer_read(b, buffer_string);
+    buffer_poke(b, p + n, buffer_u8, c);
+    buffer_seek(b, buffer_seek_start, p + n);
+    return s;
+}
+
+function __crystal_decode_value(b) {
+    switch chr(buffer_read(b, buffer_u8)) {
+        case "n":
+            return undefined;
+        case "i":
+            return int64(__crystal_decode_token(b, ord(";")));
+        case "f":
+            return real(__crystal_decode_token(b, ord(";")));
+        case "I":
+            return __crystal_decode_bits(b);
+        case "F":
+            buffer_poke(global.__crystal_bits_buffer, 0, buffer_u64, __crystal_decode_bits(b));
+            return buffer_peek(global.__crystal_bits_buffer, 0, buffer_f64);
+        case "b":
+            return buffer_read(b, buffer_u8) == ord("1");
+        case "s":
+            return __crystal_decode_field(b);
+        case "S":
+            return base64_decode(__crystal_decode_field(b));
+        case "x":
+            return buffer_base64_decode(__crystal_decode_field(b));
+        case "a":
+            var n = real(__crystal_decode_token(b, ord(":")));
+            var r = array_create(n);
+            for (var i = 0; i < n; i++)
+                r[i] = __crystal_decode_value(b);
+            return r;
+        case "o":
+            n = real(__crystal_decode_token(b, ord(":")));
+            r = {};
+            for (var i = 0; i < n; i++) {
+                var name = __crystal_decode_value(b);
+                r[$ name] = __crystal_decode_value(b);
+            }
             return r;
     }
 }